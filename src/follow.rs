@@ -0,0 +1,119 @@
+use std::fs::{self, File, Metadata};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use m_o::value::parse::parse_complete_value;
+
+use crate::opt::Opt;
+
+/// How long to wait before checking a followed file for new lines.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Pretty-prints `line` if the whole line is a Python data expression, otherwise echoes it.
+fn format_line(line: &str, options: &Opt) {
+    let line = line.trim_end_matches(['\n', '\r']);
+    match parse_complete_value(line.trim()) {
+        Ok((_rest, value)) => {
+            crate::pretty_print(&options.filter.apply(&value), options);
+        }
+        Err(_) => println!("{}", line),
+    }
+}
+
+/// Formats stdin one line at a time until it is closed.
+pub fn follow_stdin(options: &Opt) -> io::Result<()> {
+    let stdin = io::stdin();
+    for line in stdin.lock().lines() {
+        format_line(&line?, options);
+    }
+    Ok(())
+}
+
+/// The device and inode of a file, which tell whether a path still names the file that was
+/// opened, or `None` where they aren't available.
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Where the last `lines` lines of `file` start, like with `tail -n`. A last line without a
+/// newline after it counts as a line.
+fn last_lines_start(file: &mut File, lines: usize) -> io::Result<u64> {
+    const CHUNK_LEN: u64 = 8192;
+
+    let len = file.metadata()?.len();
+    if lines == 0 {
+        return Ok(len);
+    }
+    let mut chunk = vec![0; CHUNK_LEN as usize];
+    let mut newlines = 0;
+    let mut end = len;
+    while end > 0 {
+        let start = end.saturating_sub(CHUNK_LEN);
+        let chunk = &mut chunk[..(end - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(chunk)?;
+        for (i, &byte) in chunk.iter().enumerate().rev() {
+            let pos = start + i as u64;
+            // The newline at the end of the file doesn't start another line.
+            if byte == b'\n' && pos + 1 < len {
+                newlines += 1;
+                if newlines == lines {
+                    return Ok(pos + 1);
+                }
+            }
+        }
+        end = start;
+    }
+    Ok(0)
+}
+
+/// Formats the last `options.lines` lines of the file at `path`, then waits for more lines to be
+/// appended to it. If the file shrinks (because it was truncated), it is read again from the
+/// start, and if `path` names another file (because it was rotated), that file is opened and
+/// read from the start.
+pub fn follow_file(path: &Path, options: &Opt) -> io::Result<()> {
+    let mut file = File::open(path)?;
+    let mut pos = last_lines_start(&mut file, options.lines)?;
+    file.seek(SeekFrom::Start(pos))?;
+    let mut reader = BufReader::new(file);
+    let mut line = String::new();
+
+    loop {
+        let read = reader.read_line(&mut line)?;
+        pos += read as u64;
+
+        if line.ends_with('\n') {
+            format_line(&line, options);
+            line.clear();
+        } else if read == 0 {
+            // We're at the end of the file, possibly holding half of a line that's still being
+            // written.
+            let metadata = reader.get_ref().metadata()?;
+            let rotated = match fs::metadata(path) {
+                Ok(current) => file_id(&current) != file_id(&metadata),
+                // The file was moved away and the new one hasn't been created yet.
+                Err(_) => false,
+            };
+            if rotated {
+                reader = BufReader::new(File::open(path)?);
+                pos = 0;
+                line.clear();
+                continue;
+            } else if metadata.len() < pos {
+                reader.seek(SeekFrom::Start(0))?;
+                pos = 0;
+                line.clear();
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
@@ -1 +1,3 @@
+pub mod pytest;
+pub mod unified_diff;
 pub mod value;
@@ -1,35 +1,268 @@
-use std::convert::TryFrom;
+use std::fs;
 use std::io::{self, Read};
+use std::path::Path;
 use std::process;
 
+use m_o::pytest::parse_assertion;
+use m_o::unified_diff;
+use m_o::value::ast::Node;
+use m_o::value::diff::diff;
+use m_o::value::highlight::{Highlight, HighlightWriter};
+use m_o::value::parse::parse_complete_value;
 use m_o::value::Value;
+use pretty::{BoxDoc, Doc};
 
+mod follow;
 mod opt;
 
-fn read_stdin() -> String {
-    let mut buf = String::new();
-    io::stdin()
-        .read_to_string(&mut buf)
-        .expect("stdin read will succeed");
-    buf
+fn is_stdin(path: &Path) -> bool {
+    path == Path::new(opt::STDIN_PATH)
 }
 
-fn parse_input(input: &str) -> Value {
-    Value::try_from(input.trim()).unwrap_or_else(|e| {
-        eprintln!("Error: Could not parse input as Python data expression!");
-        eprintln!("\t{:?}", e);
-        process::exit(1);
-    })
+fn display_name(path: &Path) -> String {
+    if is_stdin(path) {
+        "standard input".to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
+fn read_input(path: &Path) -> io::Result<String> {
+    if is_stdin(path) {
+        let mut buf = String::new();
+        io::stdin().read_to_string(&mut buf)?;
+        Ok(buf)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+/// Like `read_input`, but reports problems on stderr.
+fn read_input_or_report(path: &Path) -> Option<String> {
+    read_input(path)
+        .map_err(|e| {
+            eprintln!("Error: Could not read {}!", display_name(path));
+            eprintln!("\t{}", e);
+        })
+        .ok()
 }
 
-fn pretty_print(value: Value, options: &opt::Opt) {
+/// Parses `input`, which was read from `path`, reporting problems on stderr.
+fn parse_input_or_report<'a>(path: &Path, input: &'a str) -> Option<Value<'a>> {
+    parse_complete_value(input.trim())
+        .map(|(_rest, value)| value)
+        .map_err(|e| {
+            eprintln!(
+                "Error: Could not parse {} as Python data expression!",
+                display_name(path)
+            );
+            eprintln!("\t{:?}", e);
+        })
+        .ok()
+}
+
+fn format_value(value: &Value, options: &opt::Opt) -> String {
     let doc = value.to_doc(&options.into());
-    println!("{}", doc.pretty(options.columns));
+    format!("{}\n", doc.pretty(options.columns))
+}
+
+/// Prints `doc` followed by a newline, coloring highlighted parts if `--color` allows it.
+fn print_highlighted(doc: &Doc<BoxDoc<Highlight>, Highlight>, options: &opt::Opt) -> bool {
+    let stdout = io::stdout();
+    let mut out = HighlightWriter::with_theme(stdout.lock(), options.theme.clone());
+    if let Err(e) = doc.render_raw(options.columns, &mut out) {
+        eprintln!("Error: Could not print output!");
+        eprintln!("\t{}", e);
+        return false;
+    }
+    println!();
+    true
+}
+
+fn pretty_print(value: &Value, options: &opt::Opt) -> bool {
+    let doc = match (options.to, options.tree) {
+        (Some(opt::OutputFormat::Json), _) => value
+            .to_json_doc(&options.into(), &options.json)
+            .map_err(|e| ("JSON", e.to_string())),
+        (Some(opt::OutputFormat::Yaml), _) => value
+            .to_yaml_doc(&options.into(), &options.yaml)
+            .map_err(|e| ("YAML", e.to_string())),
+        (Some(opt::OutputFormat::Toml), _) => value
+            .to_toml_doc(&options.into(), &options.toml)
+            .map_err(|e| ("TOML", e.to_string())),
+        (None, Some(guides)) => Ok(value.to_tree_doc(&options.into(), guides)),
+        (None, None) => Ok(value.to_doc(&options.into())),
+    };
+    let doc = match doc {
+        Ok(doc) => doc,
+        Err((format, message)) => {
+            eprintln!("Error: Could not convert the value to {}!", format);
+            eprintln!("\t{}", message);
+            return false;
+        }
+    };
+    print_highlighted(&doc, options)
+}
+
+/// Reads, parses and pretty-prints the file at `path`, or checks or rewrites it in place if
+/// `--check` or `--write` were given, or prints its syntax tree if `--dump-ast` was given.
+/// Problems are reported on stderr, in which case `false` is returned.
+fn format_file(path: &Path, options: &opt::Opt) -> bool {
+    let input = match read_input_or_report(path) {
+        Some(input) => input,
+        None => return false,
+    };
+    let value = match parse_input_or_report(path, &input) {
+        Some(value) => value,
+        None => return false,
+    };
+
+    if options.dump_ast {
+        println!("{}", Node::new(&input, &value));
+        return true;
+    }
+    let value = options.filter.apply(&value);
+
+    if options.check {
+        let formatted = format_value(&value, options);
+        if formatted != input {
+            eprintln!("would reformat {}", display_name(path));
+            // Failing to print the diff doesn't change the outcome of the check.
+            let _ = unified_diff::write_unified_diff(
+                &mut io::stdout().lock(),
+                &display_name(path),
+                &input,
+                &formatted,
+                options.theme.as_ref(),
+            );
+            return false;
+        }
+    } else if options.write && !is_stdin(path) {
+        let formatted = format_value(&value, options);
+        if formatted != input {
+            if let Err(e) = fs::write(path, formatted) {
+                eprintln!("Error: Could not write {}!", display_name(path));
+                eprintln!("\t{}", e);
+                return false;
+            }
+            eprintln!("reformatted {}", display_name(path));
+        }
+    } else {
+        return pretty_print(&value, options);
+    }
+
+    true
+}
+
+/// Prints the structural differences between the values in the files at `old` and `new`.
+/// Returns `false` if they differ or if either couldn't be parsed.
+fn diff_files(old: &Path, new: &Path, options: &opt::Opt) -> bool {
+    let (old_input, new_input) = match (read_input_or_report(old), read_input_or_report(new)) {
+        (Some(old_input), Some(new_input)) => (old_input, new_input),
+        _ => return false,
+    };
+    let (old_value, new_value) = match (
+        parse_input_or_report(old, &old_input),
+        parse_input_or_report(new, &new_input),
+    ) {
+        (Some(old_value), Some(new_value)) => (old_value, new_value),
+        _ => return false,
+    };
+
+    let (old_value, new_value) = (
+        options.filter.apply(&old_value),
+        options.filter.apply(&new_value),
+    );
+    let diff = diff(&old_value, &new_value);
+    print_highlighted(&diff.to_doc(&options.into()), options) && diff.is_same()
+}
+
+/// Echoes the pytest output in the file at `path`, replacing each assertion failure with both
+/// sides of the comparison pretty-printed, followed by their differences.
+fn format_pytest_file(path: &Path, options: &opt::Opt) -> bool {
+    let input = match read_input_or_report(path) {
+        Some(input) => input,
+        None => return false,
+    };
+
+    for line in input.lines() {
+        let (left, right) = match parse_assertion(line) {
+            Some(sides) => sides,
+            None => {
+                println!("{}", line);
+                continue;
+            }
+        };
+
+        let (left, right) = (options.filter.apply(&left), options.filter.apply(&right));
+        for &(label, value) in &[("left:", &left), ("right:", &right)] {
+            println!("{}", label);
+            if !pretty_print(value, options) {
+                return false;
+            }
+        }
+        println!("diff:");
+        if !print_highlighted(&diff(&left, &right).to_doc(&options.into()), options) {
+            return false;
+        }
+    }
+
+    true
+}
+
+fn follow(options: &opt::Opt) {
+    let path = match options.files[..] {
+        [ref path] => path,
+        _ => {
+            eprintln!("Error: Only one file can be followed at a time!");
+            process::exit(1);
+        }
+    };
+
+    let result = if is_stdin(path) {
+        follow::follow_stdin(options)
+    } else {
+        follow::follow_file(path, options)
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error: Could not follow {}!", display_name(path));
+        eprintln!("\t{}", e);
+        process::exit(1);
+    }
 }
 
 fn main() {
     let options = opt::Opt::from_args();
-    let input = read_stdin();
-    let value = parse_input(&input);
-    pretty_print(value, &options);
+
+    if let Some(opt::Command::Diff { ref old, ref new }) = options.command {
+        if !diff_files(old, new, &options) {
+            process::exit(1);
+        }
+        return;
+    }
+
+    if options.follow {
+        follow(&options);
+        return;
+    }
+
+    let mut all_ok = true;
+    for (i, path) in options.files.iter().enumerate() {
+        if options.headers {
+            if i > 0 {
+                println!();
+            }
+            println!("==> {} <==", display_name(path));
+        }
+        all_ok &= if options.pytest {
+            format_pytest_file(path, &options)
+        } else {
+            format_file(path, &options)
+        };
+    }
+
+    if !all_ok {
+        process::exit(1);
+    }
 }
@@ -1,36 +1,463 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+use std::str::FromStr;
+
+use m_o::value::filter::{default_redacted_fields, FieldPattern, Filter, HiddenValue};
+use m_o::value::json::JsonOptions;
 use m_o::value::print::PrintOptions;
+use m_o::value::quotes::QuoteStyle;
+use m_o::value::theme::Theme;
+use m_o::value::toml::TomlOptions;
+use m_o::value::tree::TreeGuides;
+use m_o::value::yaml::YamlOptions;
 use structopt::StructOpt;
 
 #[derive(StructOpt, Debug)]
 #[structopt(about, rename_all = "kebab-case")]
 struct HiddenOpt {
     /// The number of spaces used for a single indentation in the output.
-    #[structopt(short, long, default_value = "4")]
+    #[structopt(short, long, default_value = "4", global = true)]
     indent: usize,
 
     /// Specifies the width of the terminal or file that the results will be printed to. If
     /// unspecified, `m-o` will try to use the width of the current terminal window. Defaults
     /// to 80 columns.
-    #[structopt(short, long)]
+    #[structopt(short, long, global = true)]
     columns: Option<usize>,
+
+    /// Put a comma after the last item of a collection when it's broken over several lines,
+    /// like `black` does.
+    #[structopt(long, global = true)]
+    trailing_comma: bool,
+
+    /// Which quotes to print strings with: `preserve`, `single` or `double`. Like `black`, the
+    /// other quotes are used for strings which would need fewer escapes with them.
+    #[structopt(
+        long,
+        default_value = "preserve",
+        possible_values = &["preserve", "single", "double"],
+        global = true
+    )]
+    quotes: QuoteStyle,
+
+    /// Print dict entries sorted by key, so that dicts built in different orders look the same.
+    #[structopt(long, global = true)]
+    sort_dicts: bool,
+
+    /// Print set members sorted by value, so that the arbitrary order of Python sets doesn't
+    /// show up in diffs.
+    #[structopt(long, global = true)]
+    sort_sets: bool,
+
+    /// Collapse collections and constructors nested more than this many levels deep into a
+    /// summary like `[...12 items]`, `{...3 keys}` or `Dog(...)`, to show the overall shape of
+    /// large values.
+    #[structopt(long, global = true, conflicts_with_all = &["write", "check"])]
+    max_depth: Option<usize>,
+
+    /// Only print this many items of long lists, tuples, sets and dicts, with a marker like `...
+    /// 9,980 more ...` in place of the rest.
+    #[structopt(long, global = true, conflicts_with_all = &["write", "check"])]
+    max_items: Option<usize>,
+
+    /// With `--max-items`, also print that many items from the end of long collections, eliding
+    /// the ones in the middle.
+    #[structopt(long, global = true, requires = "max-items")]
+    keep_last_items: bool,
+
+    /// Only print this many characters of long strings, with a marker like `... (49,990 more
+    /// chars)` after them.
+    #[structopt(long, global = true, conflicts_with_all = &["write", "check"])]
+    max_string_length: Option<usize>,
+
+    /// Split strings which don't fit on their line into implicitly concatenated chunks, wrapped
+    /// in parentheses so that the output is still valid Python.
+    #[structopt(long, global = true, conflicts_with_all = &["write", "check"])]
+    wrap_strings: bool,
+
+    /// Align the `=` of kwargs and the `:` of dict entries into a column when a constructor or
+    /// dict is broken over several lines.
+    #[structopt(long, global = true)]
+    align: bool,
+
+    /// Print lists of calls of the same constructor with the same kwargs as tables, with a column
+    /// for each kwarg.
+    #[structopt(long, global = true, conflicts_with_all = &["write", "check"])]
+    tables: bool,
+
+    /// Print values as an outline like the `tree` command's, with each item of a collection or
+    /// constructor on its own line below it.
+    #[structopt(long, global = true, conflicts_with_all = &["write", "check"])]
+    tree: bool,
+
+    /// Draw the branches of `--tree` with ASCII characters instead of box-drawing characters.
+    #[structopt(long, global = true, requires = "tree")]
+    ascii: bool,
+
+    /// Leave out kwargs whose values are `None`, `False`, or empty strings or collections, which
+    /// are usually defaults. Constructors which had kwargs left out get a trailing `...`
+    /// argument.
+    #[structopt(long, global = true, conflicts_with_all = &["write", "check", "dump-ast"])]
+    hide_empty: bool,
+
+    /// Like `--hide-empty`, but only leave out kwargs with some kinds of values: `none`, `false`
+    /// or `empty` strings and collections.
+    #[structopt(
+        long,
+        use_delimiter = true,
+        number_of_values = 1,
+        possible_values = &["none", "false", "empty"],
+        global = true,
+        conflicts_with_all = &["write", "check", "dump-ast"]
+    )]
+    hide: Vec<HiddenValue>,
+
+    /// Only print the kwargs and dict entries whose paths match these patterns, and the ones
+    /// which contain them. A path is the kwarg names and dict keys leading to a field, joined by
+    /// dots, and a pattern matches the end of it, so `id` matches all `id` fields while
+    /// `*.error` only matches the ones nested in another field. `*` and `?` match any characters
+    /// or a single character within a segment.
+    #[structopt(
+        long,
+        use_delimiter = true,
+        number_of_values = 1,
+        global = true,
+        conflicts_with_all = &["write", "check", "dump-ast"]
+    )]
+    only: Vec<FieldPattern>,
+
+    /// Leave out the kwargs and dict entries whose paths match these patterns, like
+    /// `_sa_instance_state` or `user.created_at`. Patterns are matched like with `--only`.
+    #[structopt(
+        long,
+        use_delimiter = true,
+        number_of_values = 1,
+        global = true,
+        conflicts_with_all = &["write", "check", "dump-ast"]
+    )]
+    exclude: Vec<FieldPattern>,
+
+    /// Redact the kwargs and dict entries which usually hold secrets, like `password`, `token`,
+    /// `secret`, `api_key` or `authorization`. Strings are replaced by as many asterisks and other
+    /// values by their type, like `int(***)`.
+    #[structopt(long, global = true, conflicts_with_all = &["write", "check", "dump-ast"])]
+    redact: bool,
+
+    /// Also redact the kwargs and dict entries whose paths match these patterns. They're matched
+    /// like with `--only`, but ignoring case.
+    #[structopt(
+        long,
+        use_delimiter = true,
+        number_of_values = 1,
+        global = true,
+        conflicts_with_all = &["write", "check", "dump-ast"]
+    )]
+    redact_field: Vec<FieldPattern>,
+
+    /// When to color the output: `auto`, `always` or `never`. With `auto`, the output is only
+    /// colored if it's going to a terminal and the `NO_COLOR` environment variable isn't set.
+    #[structopt(
+        long,
+        default_value = "auto",
+        possible_values = &["auto", "always", "never"],
+        global = true
+    )]
+    color: ColorChoice,
+
+    /// The colors to use: either one of the built-in themes `dark` and `light`, or the path of
+    /// a theme file. Each line of a theme file assigns a color and any of `bold`, `italic` and
+    /// `underline` to a kind of text, like `constructor = blue bold`.
+    #[structopt(long, env = "M_O_THEME", default_value = "dark", global = true)]
+    theme: String,
+
+    /// Keep reading the input as new lines are appended to it, like `tail -f`. Each line is
+    /// formatted on its own, and lines that aren't Python data expressions are echoed as-is. A
+    /// file is only read from its last `--lines` lines.
+    #[structopt(short, long)]
+    follow: bool,
+
+    /// With `--follow`, how many lines from the end of the file to start with, like `tail -n`.
+    /// Defaults to 10.
+    #[structopt(short = "n", long, requires = "follow")]
+    lines: Option<usize>,
+
+    /// Reformat the input files in place instead of printing them. Unless `--columns` is given,
+    /// files are formatted to 80 columns regardless of the terminal width.
+    #[structopt(short, long, conflicts_with_all = &["check", "follow"])]
+    write: bool,
+
+    /// Instead of printing the formatted input, exit with a nonzero status and list the input
+    /// files whose formatting would change, along with a diff of the changes. Unless `--columns` is
+    /// given, files are checked against 80 columns regardless of the terminal width.
+    #[structopt(long, conflicts_with = "follow")]
+    check: bool,
+
+    /// Treat the input as pytest output. Assertion failures like `E   assert <left> == <right>`
+    /// are replaced by both sides pretty-printed along with their differences, as shown by `m-o
+    /// diff`. All other lines are echoed as-is.
+    #[structopt(long, conflicts_with_all = &["write", "check", "follow"])]
+    pytest: bool,
+
+    /// Instead of pretty-printing the input, print its syntax tree, with the kind of each node
+    /// and the byte range of the input it was parsed from.
+    #[structopt(long, conflicts_with_all = &["write", "check", "follow", "pytest", "tree"])]
+    dump_ast: bool,
+
+    /// Instead of pretty-printing the input, convert it to another format: `json`, `yaml` or
+    /// `toml`. Only constructors and dicts can be converted to TOML, and they can't contain
+    /// `None`.
+    #[structopt(
+        long,
+        possible_values = &["json", "yaml", "toml"],
+        conflicts_with_all = &["write", "check", "pytest", "tree", "dump-ast"]
+    )]
+    to: Option<OutputFormat>,
+
+    /// With `--to json`, print each value on one line without spaces.
+    #[structopt(long, requires = "to")]
+    compact: bool,
+
+    /// The key of the constructor name in the JSON objects, YAML mappings or TOML tables that
+    /// constructors are converted to. Their positional args are converted to an array under
+    /// `__args__`. Defaults to `__type__` with `--to json`, while with `--to yaml` constructors
+    /// are tagged with their names instead, like `!Dog`, and with `--to toml` their names are
+    /// left out.
+    #[structopt(long, requires = "to")]
+    type_field: Option<String>,
+
+    /// Print a `==> file <==` header before the output for each input file.
+    #[structopt(short = "H", long)]
+    headers: bool,
+
+    /// The files to read Python data from. Use `-` to read from stdin. If unspecified, `m-o`
+    /// reads from stdin.
+    #[structopt(parse(from_os_str))]
+    files: Vec<PathBuf>,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(rename_all = "kebab-case")]
+pub enum Command {
+    /// Shows the structural differences between two Python data expressions. Constructor kwargs
+    /// are matched by name and dict entries by key. Removed parts are marked as `[-...-]` and
+    /// added parts as `{+...+}`.
+    Diff {
+        /// The file containing the old value. Use `-` to read from stdin.
+        #[structopt(parse(from_os_str))]
+        old: PathBuf,
+
+        /// The file containing the new value. Use `-` to read from stdin.
+        #[structopt(parse(from_os_str))]
+        new: PathBuf,
+    },
+}
+
+/// The formats that `--to` converts values to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Json,
+    Yaml,
+    Toml,
 }
 
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(OutputFormat::Json),
+            "yaml" => Ok(OutputFormat::Yaml),
+            "toml" => Ok(OutputFormat::Toml),
+            _ => Err(format!("unknown output format `{}`", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!("unknown color choice `{}`", s)),
+        }
+    }
+}
+
+impl ColorChoice {
+    /// See https://no-color.org.
+    fn no_color() -> bool {
+        env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+    }
+
+    fn should_color(self) -> bool {
+        match self {
+            ColorChoice::Auto => termion::is_tty(&io::stdout()) && !Self::no_color(),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+/// Looks up the built-in theme called `name`, or else reads the theme file at that path.
+fn load_theme(name: &str) -> Theme {
+    if let Some(theme) = Theme::named(name) {
+        return theme;
+    }
+
+    let text = fs::read_to_string(name).unwrap_or_else(|e| {
+        eprintln!("Error: Could not read theme file `{}`!", name);
+        eprintln!("\t{}", e);
+        process::exit(1);
+    });
+    Theme::parse(&text).unwrap_or_else(|e| {
+        eprintln!("Error: Could not parse theme file `{}`!", name);
+        eprintln!("\t{}", e);
+        process::exit(1);
+    })
+}
+
+/// The file name which stands for stdin.
+pub const STDIN_PATH: &str = "-";
+
+const DEFAULT_COLUMNS: usize = 80;
+
 fn terminal_width() -> usize {
     termion::terminal_size()
         .map(|(w, _h)| w as usize)
-        .unwrap_or(80)
+        .unwrap_or(DEFAULT_COLUMNS)
 }
 
 pub struct Opt {
     pub indent: usize,
     pub columns: usize,
+    pub trailing_comma: bool,
+    pub quotes: QuoteStyle,
+    pub sort_dicts: bool,
+    pub sort_sets: bool,
+    pub max_depth: Option<usize>,
+    pub max_items: Option<usize>,
+    pub keep_last_items: bool,
+    pub max_string_length: Option<usize>,
+    pub wrap_strings: bool,
+    pub align: bool,
+    pub tables: bool,
+    /// How to draw values as trees, or `None` to pretty-print them as usual.
+    pub tree: Option<TreeGuides>,
+    /// What to leave out of values before printing them.
+    pub filter: Filter,
+    /// The theme to color the output with, or `None` if it shouldn't be colored.
+    pub theme: Option<Theme>,
+    pub follow: bool,
+    /// How many lines from the end of a followed file to start with.
+    pub lines: usize,
+    pub write: bool,
+    pub check: bool,
+    pub pytest: bool,
+    pub dump_ast: bool,
+    /// The format to convert values to, or `None` to pretty-print them.
+    pub to: Option<OutputFormat>,
+    pub json: JsonOptions,
+    pub yaml: YamlOptions,
+    pub toml: TomlOptions,
+    pub headers: bool,
+    pub files: Vec<PathBuf>,
+    pub command: Option<Command>,
 }
 
 impl From<HiddenOpt> for Opt {
     fn from(hidden: HiddenOpt) -> Self {
         Opt {
             indent: hidden.indent,
-            columns: hidden.columns.unwrap_or_else(terminal_width),
+            columns: hidden.columns.unwrap_or_else(|| {
+                // Formatting files in place shouldn't depend on who ran `m-o`, or where.
+                if hidden.write || hidden.check {
+                    DEFAULT_COLUMNS
+                } else {
+                    terminal_width()
+                }
+            }),
+            trailing_comma: hidden.trailing_comma,
+            quotes: hidden.quotes,
+            sort_dicts: hidden.sort_dicts,
+            sort_sets: hidden.sort_sets,
+            max_depth: hidden.max_depth,
+            max_items: hidden.max_items,
+            keep_last_items: hidden.keep_last_items,
+            max_string_length: hidden.max_string_length,
+            wrap_strings: hidden.wrap_strings,
+            align: hidden.align,
+            tables: hidden.tables,
+            tree: match (hidden.tree, hidden.ascii) {
+                (false, _) => None,
+                (true, false) => Some(TreeGuides::Unicode),
+                (true, true) => Some(TreeGuides::Ascii),
+            },
+            filter: Filter {
+                hide: if hidden.hide_empty {
+                    HiddenValue::ALL.to_vec()
+                } else {
+                    hidden.hide
+                },
+                only: hidden.only,
+                exclude: hidden.exclude,
+                redact: if hidden.redact {
+                    default_redacted_fields()
+                        .into_iter()
+                        .chain(hidden.redact_field)
+                        .collect()
+                } else {
+                    hidden.redact_field
+                },
+                hide_silently: hidden.to.is_some(),
+            },
+            theme: if hidden.color.should_color() {
+                Some(load_theme(&hidden.theme))
+            } else {
+                None
+            },
+            follow: hidden.follow,
+            lines: hidden.lines.unwrap_or(10),
+            write: hidden.write,
+            check: hidden.check,
+            pytest: hidden.pytest,
+            dump_ast: hidden.dump_ast,
+            to: hidden.to,
+            json: JsonOptions {
+                compact: hidden.compact,
+                type_field: hidden
+                    .type_field
+                    .clone()
+                    .unwrap_or_else(|| JsonOptions::default().type_field),
+            },
+            yaml: YamlOptions {
+                type_field: hidden.type_field.clone(),
+            },
+            toml: TomlOptions {
+                type_field: hidden.type_field,
+            },
+            headers: hidden.headers,
+            files: if hidden.files.is_empty() {
+                vec![PathBuf::from(STDIN_PATH)]
+            } else {
+                hidden.files
+            },
+            command: hidden.command,
         }
     }
 }
@@ -41,11 +468,22 @@ impl Opt {
     }
 }
 
-impl Into<PrintOptions> for &Opt {
-    fn into(self) -> PrintOptions {
+impl From<&Opt> for PrintOptions {
+    fn from(opt: &Opt) -> Self {
         PrintOptions {
-            indent: self.indent,
-            columns: self.columns,
+            indent: opt.indent,
+            columns: opt.columns,
+            trailing_comma: opt.trailing_comma,
+            quotes: opt.quotes,
+            sort_dicts: opt.sort_dicts,
+            sort_sets: opt.sort_sets,
+            max_depth: opt.max_depth,
+            max_items: opt.max_items,
+            keep_last_items: opt.keep_last_items,
+            max_string_length: opt.max_string_length,
+            wrap_strings: opt.wrap_strings,
+            align: opt.align,
+            tables: opt.tables,
         }
     }
 }
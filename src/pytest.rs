@@ -0,0 +1,47 @@
+//! Support for the assertion failures that pytest prints, like
+//! `E   AssertionError: assert Dog(name='Pip') == Dog(name='Pep')`.
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{char, multispace0, multispace1},
+    combinator::{all_consuming, map, opt},
+    sequence::{delimited, preceded, separated_pair, terminated, tuple},
+    IResult,
+};
+
+use crate::value::parse::parse_value;
+use crate::value::Value;
+
+/// Matches the start of a line of pytest output which explains an assertion failure, which is
+/// either an `E` in the margin or an `AssertionError:`.
+fn error_prefix(input: &str) -> IResult<&str, ()> {
+    let assertion_error = || terminated(tag("AssertionError:"), multispace0);
+    map(
+        alt((
+            preceded(tuple((char('E'), multispace1)), opt(assertion_error())),
+            map(assertion_error(), Some),
+        )),
+        |_| (),
+    )(input)
+}
+
+fn assertion(input: &str) -> IResult<&str, (Value<'_>, Value<'_>)> {
+    preceded(
+        tuple((multispace0, error_prefix, tag("assert"), multispace1)),
+        separated_pair(
+            parse_value,
+            delimited(multispace0, tag("=="), multispace0),
+            parse_value,
+        ),
+    )(input)
+}
+
+/// Parses a line of pytest output like `E   assert <left> == <right>`, returning both sides
+/// of the comparison. Returns `None` for any other line, including the lines of source code
+/// that pytest quotes, since those don't contain values.
+pub fn parse_assertion(line: &str) -> Option<(Value<'_>, Value<'_>)> {
+    all_consuming(terminated(assertion, multispace0))(line)
+        .ok()
+        .map(|(_rest, sides)| sides)
+}
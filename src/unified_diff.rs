@@ -0,0 +1,55 @@
+use std::io::{self, Write};
+
+use similar::{ChangeTag, TextDiff};
+use termion::style;
+
+use crate::value::theme::{Style, Theme};
+
+/// The number of unchanged lines shown around each change.
+const CONTEXT_LINES: usize = 3;
+
+/// Writes `line` followed by a newline, in the style `style` if one is given.
+fn write_line<W: Write>(out: &mut W, line: &str, style: Option<&Style>) -> io::Result<()> {
+    match style {
+        Some(style) => writeln!(out, "{}{}{}", style.escape_code(), line, style::Reset),
+        None => writeln!(out, "{}", line),
+    }
+}
+
+/// Writes a unified diff between the `current` contents of the file called `name` and its
+/// `formatted` contents. Removed and added lines are styled using `theme`, if one is given.
+pub fn write_unified_diff<W: Write>(
+    out: &mut W,
+    name: &str,
+    current: &str,
+    formatted: &str,
+    theme: Option<&Theme>,
+) -> io::Result<()> {
+    let diff = TextDiff::from_lines(current, formatted);
+    let mut unified = diff.unified_diff();
+    unified.context_radius(CONTEXT_LINES);
+
+    write_line(out, &format!("--- {}\t(current)", name), None)?;
+    write_line(out, &format!("+++ {}\t(formatted)", name), None)?;
+
+    for hunk in unified.iter_hunks() {
+        let header_style = theme.map(|theme| &theme.punctuation);
+        write_line(out, &hunk.header().to_string(), header_style)?;
+
+        for change in hunk.iter_changes() {
+            let style = theme.and_then(|theme| match change.tag() {
+                ChangeTag::Delete => Some(&theme.deleted),
+                ChangeTag::Insert => Some(&theme.inserted),
+                ChangeTag::Equal => None,
+            });
+            let line = change.value().trim_end_matches('\n');
+            write_line(out, &format!("{}{}", change.tag(), line), style)?;
+
+            if change.missing_newline() {
+                write_line(out, "\\ No newline at end of file", None)?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+use std::ops::Range;
+
+use nom::IResult;
+
+use super::{Arg, Value};
+use crate::value::parse::{parse_float, parse_int};
+
+/// The kinds of nodes in the syntax tree of a Python data expression.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Bool,
+    Str,
+    Int,
+    Float,
+    Tuple,
+    List,
+    Set,
+    Dict,
+    /// A `key: value` pair in a dict.
+    Entry,
+    Constructor,
+    /// A positional argument of a constructor.
+    Arg,
+    /// A `name=value` argument of a constructor.
+    Kwarg,
+    Symbol,
+}
+
+/// A node of the syntax tree of a parsed `Value`, with its position in the source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node<'a> {
+    pub kind: Kind,
+    /// The byte range of the source the node was parsed from.
+    pub span: Range<usize>,
+    /// The name of a constructor or kwarg.
+    pub name: Option<&'a str>,
+    pub children: Vec<Node<'a>>,
+    /// The source the node was parsed from.
+    pub text: &'a str,
+}
+
+/// Finds the spans of the parts of a value by walking the value and its source together, once.
+/// The source is known to parse as the value, so only the punctuation and whitespace between the
+/// parts have to be skipped.
+struct Spans<'a> {
+    source: &'a str,
+}
+
+impl<'a> Spans<'a> {
+    /// Where the whitespace at `pos` ends.
+    fn skip_space(&self, pos: usize) -> usize {
+        let rest = &self.source[pos..];
+        pos + rest.len() - rest.trim_start().len()
+    }
+
+    /// Where `punctuation` at `pos` ends, along with any whitespace around it, or `pos` if it
+    /// isn't there.
+    fn skip_punctuation(&self, punctuation: char, pos: usize) -> usize {
+        let start = self.skip_space(pos);
+        if self.source[start..].starts_with(punctuation) {
+            self.skip_space(start + punctuation.len_utf8())
+        } else {
+            pos
+        }
+    }
+
+    /// Where the `close` bracket after the last item of a collection at `pos` ends, skipping the
+    /// whitespace and trailing comma that may come before it.
+    fn skip_close(&self, close: char, pos: usize) -> usize {
+        let pos = self.skip_punctuation(',', pos);
+        self.skip_space(pos) + close.len_utf8()
+    }
+
+    /// Where the number at `start`, as matched by `parser`, ends.
+    fn skip_number(
+        &self,
+        parser: impl Fn(&'a str) -> IResult<&'a str, Value<'a>>,
+        start: usize,
+    ) -> usize {
+        match parser(&self.source[start..]) {
+            Ok((rest, _)) => self.source.len() - rest.len(),
+            Err(_) => start,
+        }
+    }
+
+    fn node(&self, kind: Kind, span: Range<usize>, children: Vec<Node<'a>>) -> Node<'a> {
+        Node {
+            kind,
+            text: &self.source[span.clone()],
+            span,
+            name: None,
+            children,
+        }
+    }
+
+    /// The node for `value`, which was parsed from the source at `start`.
+    fn value(&self, value: &Value<'a>, start: usize) -> Node<'a> {
+        let leaf = |kind, len| self.node(kind, start..start + len, Vec::new());
+        match *value {
+            Value::Bool(x) => leaf(Kind::Bool, if x { "True" } else { "False" }.len()),
+            Value::Str(ref raw) => leaf(Kind::Str, raw.len()),
+            Value::Symbol(symbol) => leaf(Kind::Symbol, symbol.len()),
+            Value::Int(_) => {
+                let end = self.skip_number(parse_int, start);
+                self.node(Kind::Int, start..end, Vec::new())
+            }
+            Value::Float(_) => {
+                let end = self.skip_number(parse_float, start);
+                self.node(Kind::Float, start..end, Vec::new())
+            }
+            Value::Tuple(ref xs) => self.items(Kind::Tuple, xs, ('(', ')'), start),
+            Value::List(ref xs) => self.items(Kind::List, xs, ('[', ']'), start),
+            Value::Set(ref xs) => self.items(Kind::Set, xs, ('{', '}'), start),
+            Value::Dict(ref pairs) => self.entries(pairs, start),
+            Value::Constructor(name, ref args) => self.args(name, args, start),
+        }
+    }
+
+    fn items(
+        &self,
+        kind: Kind,
+        xs: &[Value<'a>],
+        (open, close): (char, char),
+        start: usize,
+    ) -> Node<'a> {
+        let mut pos = self.skip_space(start + open.len_utf8());
+        let children = xs
+            .iter()
+            .map(|x| {
+                let node = self.value(x, pos);
+                pos = self.skip_punctuation(',', node.span.end);
+                node
+            })
+            .collect();
+        self.node(kind, start..self.skip_close(close, pos), children)
+    }
+
+    fn entries(&self, pairs: &[(Value<'a>, Value<'a>)], start: usize) -> Node<'a> {
+        let mut pos = self.skip_space(start + 1);
+        let children = pairs
+            .iter()
+            .map(|(key, value)| {
+                let key = self.value(key, pos);
+                let value = self.value(value, self.skip_punctuation(':', key.span.end));
+                pos = self.skip_punctuation(',', value.span.end);
+                self.node(
+                    Kind::Entry,
+                    key.span.start..value.span.end,
+                    vec![key, value],
+                )
+            })
+            .collect();
+        self.node(Kind::Dict, start..self.skip_close('}', pos), children)
+    }
+
+    fn args(&self, name: &'a str, args: &[Arg<'a>], start: usize) -> Node<'a> {
+        let mut pos = self.skip_space(start + name.len() + 1);
+        let children = args
+            .iter()
+            .map(|arg| {
+                let node = match *arg {
+                    Arg::Arg(ref value) => {
+                        let value = self.value(value, pos);
+                        self.node(Kind::Arg, value.span.clone(), vec![value])
+                    }
+                    Arg::Kwarg(name, ref value) => {
+                        let value_start = self.skip_punctuation('=', pos + name.len());
+                        let value = self.value(value, value_start);
+                        let mut node = self.node(Kind::Kwarg, pos..value.span.end, vec![value]);
+                        node.name = Some(name);
+                        node
+                    }
+                };
+                pos = self.skip_punctuation(',', node.span.end);
+                node
+            })
+            .collect();
+        let mut node = self.node(
+            Kind::Constructor,
+            start..self.skip_close(')', pos),
+            children,
+        );
+        node.name = Some(name);
+        node
+    }
+}
+
+impl<'a> Node<'a> {
+    /// The syntax tree of `value`, which was parsed from `source` with any whitespace around it
+    /// trimmed.
+    pub fn new(source: &'a str, value: &Value<'a>) -> Node<'a> {
+        let start = source.len() - source.trim_start().len();
+        Spans { source }.value(value, start)
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        write!(
+            f,
+            "{:indent$}{:?} {}..{}",
+            "",
+            self.kind,
+            self.span.start,
+            self.span.end,
+            indent = depth * 2
+        )?;
+        if let Some(name) = self.name {
+            write!(f, " {}", name)?;
+        } else if self.children.is_empty() {
+            write!(f, " {}", self.text)?;
+        }
+        for child in &self.children {
+            writeln!(f)?;
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints the tree one node per line, indented by its depth, with each node's kind, span and
+/// either its name or its source.
+impl<'a> fmt::Display for Node<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
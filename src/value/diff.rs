@@ -0,0 +1,292 @@
+use pretty::{BoxDoc, Doc};
+
+use super::Value;
+use crate::value::highlight::Highlight;
+use crate::value::print::{highlighted, PrintOptions};
+use crate::value::Arg;
+
+/// The differences between two values, as computed by [`diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Diff<'v, 'a> {
+    /// Both values are equal.
+    Same(&'v Value<'a>),
+    /// The values have nothing in common, so the old one was replaced by the new one.
+    Replaced(&'v Value<'a>, &'v Value<'a>),
+    /// Both values are the same kind of collection (or are constructed with the same name), but
+    /// some of their entries differ.
+    Collection(&'v Value<'a>, Vec<Entry<'v, 'a>>),
+}
+
+/// What identifies an entry of a collection, besides its position.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Key<'v, 'a> {
+    Kwarg(&'a str),
+    Dict(&'v Value<'a>),
+}
+
+/// A single entry of two collections that are being compared.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Entry<'v, 'a> {
+    /// The entry is only in the old collection.
+    Removed(Option<Key<'v, 'a>>, &'v Value<'a>),
+    /// The entry is only in the new collection.
+    Added(Option<Key<'v, 'a>>, &'v Value<'a>),
+    /// The entry is in both collections, possibly with different values.
+    Kept(Option<Key<'v, 'a>>, Diff<'v, 'a>),
+}
+
+/// Structurally compares `old` to `new`. Constructor kwargs are matched by name and dict entries
+/// are matched by key, so only the values that actually changed are reported as such.
+pub fn diff<'v, 'a>(old: &'v Value<'a>, new: &'v Value<'a>) -> Diff<'v, 'a> {
+    if old == new {
+        return Diff::Same(old);
+    }
+
+    let entries = match (old, new) {
+        (Value::List(xs), Value::List(ys)) | (Value::Tuple(xs), Value::Tuple(ys)) => {
+            diff_seqs(xs, ys)
+        }
+        (Value::Set(xs), Value::Set(ys)) => diff_sets(xs, ys),
+        (Value::Dict(xs), Value::Dict(ys)) => diff_dicts(xs, ys),
+        (Value::Constructor(x, xs), Value::Constructor(y, ys)) if x == y => diff_args(xs, ys),
+        _ => return Diff::Replaced(old, new),
+    };
+
+    // Sets, dicts and kwargs which were only reordered have nothing to mark.
+    let reordered = entries
+        .iter()
+        .all(|entry| matches!(entry, Entry::Kept(_, Diff::Same(_))));
+    if reordered {
+        return Diff::Same(new);
+    }
+
+    Diff::Collection(old, entries)
+}
+
+/// Matches up the items of two sequences using their longest common subsequence. Runs of items
+/// in between are compared pairwise.
+fn diff_seqs<'v, 'a>(xs: &'v [Value<'a>], ys: &'v [Value<'a>]) -> Vec<Entry<'v, 'a>> {
+    // `lcs[i][j]` is the length of the longest common subsequence of `xs[i..]` and `ys[j..]`.
+    let mut lcs = vec![vec![0; ys.len() + 1]; xs.len() + 1];
+    for i in (0..xs.len()).rev() {
+        for j in (0..ys.len()).rev() {
+            lcs[i][j] = if xs[i] == ys[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut entries = Vec::new();
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (0, 0);
+
+    while i < xs.len() || j < ys.len() {
+        if i < xs.len() && j < ys.len() && xs[i] == ys[j] {
+            pair_up(&mut entries, &mut removed, &mut added);
+            entries.push(Entry::Kept(None, Diff::Same(&xs[i])));
+            i += 1;
+            j += 1;
+        } else if j == ys.len() || (i < xs.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            removed.push(&xs[i]);
+            i += 1;
+        } else {
+            added.push(&ys[j]);
+            j += 1;
+        }
+    }
+    pair_up(&mut entries, &mut removed, &mut added);
+
+    entries
+}
+
+/// Moves a run of `removed` and `added` items into `entries`, comparing them pairwise.
+fn pair_up<'v, 'a>(
+    entries: &mut Vec<Entry<'v, 'a>>,
+    removed: &mut Vec<&'v Value<'a>>,
+    added: &mut Vec<&'v Value<'a>>,
+) {
+    let paired = removed.len().min(added.len());
+    entries.extend(
+        removed
+            .iter()
+            .zip(added.iter())
+            .map(|(x, y)| Entry::Kept(None, diff(x, y))),
+    );
+    entries.extend(
+        removed
+            .drain(..)
+            .skip(paired)
+            .map(|x| Entry::Removed(None, x)),
+    );
+    entries.extend(added.drain(..).skip(paired).map(|y| Entry::Added(None, y)));
+}
+
+fn diff_sets<'v, 'a>(xs: &'v [Value<'a>], ys: &'v [Value<'a>]) -> Vec<Entry<'v, 'a>> {
+    let kept_or_removed = xs.iter().map(|x| {
+        if ys.contains(x) {
+            Entry::Kept(None, Diff::Same(x))
+        } else {
+            Entry::Removed(None, x)
+        }
+    });
+    let added = ys
+        .iter()
+        .filter(|y| !xs.contains(y))
+        .map(|y| Entry::Added(None, y));
+
+    kept_or_removed.chain(added).collect()
+}
+
+fn diff_dicts<'v, 'a>(
+    xs: &'v [(Value<'a>, Value<'a>)],
+    ys: &'v [(Value<'a>, Value<'a>)],
+) -> Vec<Entry<'v, 'a>> {
+    let lookup = |pairs: &'v [(Value<'a>, Value<'a>)], key: &Value<'a>| {
+        pairs.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    };
+
+    let kept_or_removed = xs.iter().map(|(key, x)| match lookup(ys, key) {
+        Some(y) => Entry::Kept(Some(Key::Dict(key)), diff(x, y)),
+        None => Entry::Removed(Some(Key::Dict(key)), x),
+    });
+    let added = ys
+        .iter()
+        .filter(|(key, _)| lookup(xs, key).is_none())
+        .map(|(key, y)| Entry::Added(Some(Key::Dict(key)), y));
+
+    kept_or_removed.chain(added).collect()
+}
+
+fn diff_args<'v, 'a>(xs: &'v [Arg<'a>], ys: &'v [Arg<'a>]) -> Vec<Entry<'v, 'a>> {
+    fn positional<'v, 'a>(args: &'v [Arg<'a>]) -> Vec<&'v Value<'a>> {
+        args.iter()
+            .filter_map(|arg| match arg {
+                Arg::Arg(value) => Some(value),
+                Arg::Kwarg(..) => None,
+            })
+            .collect()
+    }
+
+    fn keyword<'v, 'a>(args: &'v [Arg<'a>]) -> Vec<(&'a str, &'v Value<'a>)> {
+        args.iter()
+            .filter_map(|arg| match arg {
+                Arg::Kwarg(name, value) => Some((*name, value)),
+                Arg::Arg(..) => None,
+            })
+            .collect()
+    }
+
+    let (xs_positional, ys_positional) = (positional(xs), positional(ys));
+    let (xs_keyword, ys_keyword) = (keyword(xs), keyword(ys));
+    let lookup = |kwargs: &[(&'a str, &'v Value<'a>)], name: &str| {
+        kwargs.iter().find(|(k, _)| *k == name).map(|(_, v)| *v)
+    };
+
+    let mut entries = Vec::new();
+
+    for (i, x) in xs_positional.iter().enumerate() {
+        entries.push(match ys_positional.get(i) {
+            Some(y) => Entry::Kept(None, diff(x, y)),
+            None => Entry::Removed(None, x),
+        });
+    }
+    for y in ys_positional.iter().skip(xs_positional.len()) {
+        entries.push(Entry::Added(None, y));
+    }
+
+    for (name, x) in xs_keyword.iter() {
+        entries.push(match lookup(&ys_keyword, name) {
+            Some(y) => Entry::Kept(Some(Key::Kwarg(name)), diff(x, y)),
+            None => Entry::Removed(Some(Key::Kwarg(name)), x),
+        });
+    }
+    for (name, y) in ys_keyword.iter() {
+        if lookup(&xs_keyword, name).is_none() {
+            entries.push(Entry::Added(Some(Key::Kwarg(name)), y));
+        }
+    }
+
+    entries
+}
+
+fn deleted<'a>(
+    doc: Doc<'a, BoxDoc<'a, Highlight>, Highlight>,
+) -> Doc<'a, BoxDoc<'a, Highlight>, Highlight> {
+    Doc::text("[-")
+        .append(doc)
+        .append(Doc::text("-]"))
+        .annotate(Highlight::Deleted)
+}
+
+fn inserted<'a>(
+    doc: Doc<'a, BoxDoc<'a, Highlight>, Highlight>,
+) -> Doc<'a, BoxDoc<'a, Highlight>, Highlight> {
+    Doc::text("{+")
+        .append(doc)
+        .append(Doc::text("+}"))
+        .annotate(Highlight::Inserted)
+}
+
+impl<'v, 'a> Key<'v, 'a> {
+    fn to_doc(&self, options: &PrintOptions) -> Doc<'a, BoxDoc<'a, Highlight>, Highlight> {
+        match *self {
+            Key::Kwarg(name) => {
+                highlighted(name, Highlight::Kwarg).append(highlighted("=", Highlight::Punctuation))
+            }
+            Key::Dict(key) => key
+                .to_doc(options)
+                .append(highlighted(":", Highlight::Punctuation))
+                .append(Doc::text(" ")),
+        }
+    }
+}
+
+impl<'v, 'a> Entry<'v, 'a> {
+    fn to_doc(&self, options: &PrintOptions) -> Doc<'a, BoxDoc<'a, Highlight>, Highlight> {
+        let key_to_doc = |key: &Option<Key<'v, 'a>>| match key {
+            Some(key) => key.to_doc(options),
+            None => Doc::nil(),
+        };
+
+        match self {
+            Entry::Removed(key, value) => deleted(key_to_doc(key).append(value.to_doc(options))),
+            Entry::Added(key, value) => inserted(key_to_doc(key).append(value.to_doc(options))),
+            Entry::Kept(key, diff) => key_to_doc(key).append(diff.to_doc(options)),
+        }
+    }
+}
+
+impl<'v, 'a> Diff<'v, 'a> {
+    /// Returns `true` if there are no differences.
+    pub fn is_same(&self) -> bool {
+        matches!(self, Diff::Same(_))
+    }
+
+    /// Lays out the new value with removed parts marked as `[-...-]` and added parts marked as
+    /// `{+...+}`, like `git diff --word-diff`. The markers are also highlighted.
+    pub fn to_doc(&self, options: &PrintOptions) -> Doc<'a, BoxDoc<'a, Highlight>, Highlight> {
+        match self {
+            Diff::Same(value) => value.to_doc(options),
+            Diff::Replaced(old, new) => deleted(old.to_doc(options))
+                .append(Doc::space())
+                .append(inserted(new.to_doc(options)))
+                .group(),
+            Diff::Collection(value, entries) => {
+                let entries = entries.iter().map(|entry| entry.to_doc(options));
+                match value {
+                    Value::List(_) => Value::seq_to_doc("[", entries, "]", false, options),
+                    Value::Tuple(_) => Value::seq_to_doc("(", entries, ")", false, options),
+                    Value::Set(_) | Value::Dict(_) => {
+                        Value::seq_to_doc("{", entries, "}", false, options)
+                    }
+                    Value::Constructor(name, _) => highlighted(*name, Highlight::Constructor)
+                        .append(Value::seq_to_doc("(", entries, ")", false, options))
+                        .group(),
+                    _ => unreachable!("only collections are compared entry by entry"),
+                }
+            }
+        }
+    }
+}
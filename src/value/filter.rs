@@ -0,0 +1,335 @@
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use super::{Arg, Value};
+use crate::value::quotes::{char_count, mask};
+
+/// Kinds of kwarg values which can be hidden, being the usual defaults of optional fields.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HiddenValue {
+    /// `None`.
+    None,
+    /// `False`.
+    False,
+    /// Empty strings and collections, like `''`, `[]` and `{}`.
+    Empty,
+}
+
+impl HiddenValue {
+    pub const ALL: [HiddenValue; 3] = [HiddenValue::None, HiddenValue::False, HiddenValue::Empty];
+
+    fn matches(self, value: &Value) -> bool {
+        match (self, value) {
+            (HiddenValue::None, Value::Symbol("None")) => true,
+            (HiddenValue::False, Value::Bool(false)) => true,
+            (HiddenValue::Empty, Value::Str(raw)) => raw.len() == 2,
+            (HiddenValue::Empty, Value::List(xs))
+            | (HiddenValue::Empty, Value::Tuple(xs))
+            | (HiddenValue::Empty, Value::Set(xs)) => xs.is_empty(),
+            (HiddenValue::Empty, Value::Dict(pairs)) => pairs.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for HiddenValue {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(HiddenValue::None),
+            "false" => Ok(HiddenValue::False),
+            "empty" => Ok(HiddenValue::Empty),
+            _ => Err(format!("unknown kind of value `{}`", s)),
+        }
+    }
+}
+
+/// A pattern for the paths of fields, being kwargs and dict entries, like `status` or
+/// `*.error`. A path is the kwarg names and dict keys leading to a field from the top level value,
+/// joined by dots. The items of collections and positional args aren't part of paths.
+///
+/// A pattern matches a field if it matches the end of its path, so `error` matches the `error`
+/// kwargs at any depth, while `*.error` only matches the ones inside another field. In each
+/// segment of a pattern, `*` stands for any characters and `?` for any single character.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldPattern {
+    segments: Vec<String>,
+}
+
+impl FieldPattern {
+    fn matches(&self, path: &[Cow<str>], ignore_case: bool) -> bool {
+        path.len() >= self.segments.len()
+            && self
+                .segments
+                .iter()
+                .zip(&path[path.len() - self.segments.len()..])
+                .all(|(segment, key)| glob_matches(segment, key, ignore_case))
+    }
+}
+
+impl FromStr for FieldPattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let segments: Vec<_> = s.split('.').map(String::from).collect();
+        if segments.iter().any(String::is_empty) {
+            return Err(format!("`{}` has an empty segment", s));
+        }
+        Ok(FieldPattern { segments })
+    }
+}
+
+/// Whether `text` matches all of the glob `pattern`, optionally ignoring ASCII case.
+fn glob_matches(pattern: &str, text: &str, ignore_case: bool) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // The position after the last `*` seen in the pattern, and where in the text it started
+    // matching, to backtrack to when the rest of the pattern doesn't match.
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, t));
+                p += 1;
+            }
+            Some(&c)
+                if c == '?'
+                    || c == text[t]
+                    || (ignore_case && c.eq_ignore_ascii_case(&text[t])) =>
+            {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    star = Some((star_p, star_t + 1));
+                    p = star_p;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// The segment of the path of a dict entry for its key: the contents of strings and the printed
+/// form of other scalars.
+fn key_segment<'a>(key: &Value<'a>) -> Cow<'a, str> {
+    match *key {
+        Value::Str(Cow::Borrowed(raw)) => Cow::Borrowed(&raw[1..raw.len() - 1]),
+        Value::Str(Cow::Owned(ref raw)) => Cow::Owned(raw[1..raw.len() - 1].to_string()),
+        Value::Symbol(symbol) => Cow::Borrowed(symbol),
+        Value::Bool(true) => Cow::Borrowed("True"),
+        Value::Bool(false) => Cow::Borrowed("False"),
+        Value::Int(x) => Cow::Owned(x.to_string()),
+        Value::Float(x) => Cow::Owned(x.to_string()),
+        _ => Cow::Borrowed(""),
+    }
+}
+
+/// Patterns for the fields which usually hold secrets, which `--redact` redacts.
+const DEFAULT_REDACTED_FIELDS: &[&str] = &[
+    "*password*",
+    "*passwd*",
+    "*secret*",
+    "*token*",
+    "*api_key*",
+    "*apikey*",
+    "*private_key*",
+    "*credential*",
+    "authorization",
+    "cookie",
+];
+
+/// The patterns of `--redact`, for the fields which usually hold secrets, like `password`,
+/// `access_token` or `api_key`.
+pub fn default_redacted_fields() -> Vec<FieldPattern> {
+    DEFAULT_REDACTED_FIELDS
+        .iter()
+        .map(|pattern| pattern.parse().unwrap())
+        .collect()
+}
+
+/// The symbol which stands in for redacted values other than strings, as the argument of their
+/// type, like `int(***)`.
+pub const REDACTED_MARKER: &str = "***";
+
+/// `value` with its contents hidden but not its type: strings are replaced by as many asterisks,
+/// and other values by their type with a `***` argument, like `int(***)` or `Token(***)`. `None`
+/// is kept, since it doesn't hold anything.
+fn redacted<'a>(value: &Value<'a>) -> Value<'a> {
+    let type_name = match *value {
+        Value::Symbol("None") => return value.clone(),
+        Value::Symbol(_) => return Value::Symbol(REDACTED_MARKER),
+        Value::Str(ref raw) => {
+            let quote = raw.chars().next().unwrap_or('\'');
+            return Value::Str(Cow::Owned(mask(quote, char_count(raw))));
+        }
+        Value::Bool(_) => "bool",
+        Value::Int(_) => "int",
+        Value::Float(_) => "float",
+        Value::Tuple(_) => "tuple",
+        Value::List(_) => "list",
+        Value::Set(_) => "set",
+        Value::Dict(_) => "dict",
+        Value::Constructor(name, _) => name,
+    };
+    Value::Constructor(type_name, vec![Arg::Arg(Value::Symbol(REDACTED_MARKER))])
+}
+
+/// The symbol which stands in for the kwargs hidden from a constructor.
+pub const HIDDEN_MARKER: &str = "...";
+
+/// Changes made to values before they're printed, to leave out the parts that aren't
+/// interesting.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    /// Kwargs with these kinds of values are left out of constructors, which get a trailing `...`
+    /// argument instead.
+    pub hide: Vec<HiddenValue>,
+    /// If any, only the fields matching these patterns are kept, along with the fields which
+    /// contain them.
+    pub only: Vec<FieldPattern>,
+    /// Fields matching these patterns are left out, along with everything in them.
+    pub exclude: Vec<FieldPattern>,
+    /// The values of fields matching these patterns, ignoring case, are replaced by ones of the
+    /// same type which don't give away their contents.
+    pub redact: Vec<FieldPattern>,
+    /// Leave kwargs out without the trailing `...` argument, which would look like part of the
+    /// data when values are converted to other formats.
+    pub hide_silently: bool,
+}
+
+impl Filter {
+    fn is_hidden(&self, value: &Value) -> bool {
+        self.hide.iter().any(|hidden| hidden.matches(value))
+    }
+
+    /// A copy of `value` with this filter applied to it and everything in it.
+    pub fn apply<'a>(&self, value: &Value<'a>) -> Value<'a> {
+        self.apply_at(value, &mut Vec::new(), self.only.is_empty())
+            .0
+    }
+
+    /// Applies this filter to `value` at `path`, keeping all of its fields if it's `selected` by
+    /// `--only`. Also returns whether any fields were kept in it.
+    fn apply_at<'a>(
+        &self,
+        value: &Value<'a>,
+        path: &mut Vec<Cow<'a, str>>,
+        selected: bool,
+    ) -> (Value<'a>, bool) {
+        match *value {
+            Value::Tuple(ref xs) => {
+                let (xs, kept_any) = self.apply_all(xs, path, selected);
+                (Value::Tuple(xs), kept_any)
+            }
+            Value::List(ref xs) => {
+                let (xs, kept_any) = self.apply_all(xs, path, selected);
+                (Value::List(xs), kept_any)
+            }
+            Value::Set(ref xs) => {
+                let (xs, kept_any) = self.apply_all(xs, path, selected);
+                (Value::Set(xs), kept_any)
+            }
+            Value::Dict(ref pairs) => {
+                let pairs: Vec<_> = pairs
+                    .iter()
+                    .filter_map(|(key, value)| {
+                        let value = self.apply_field(key_segment(key), value, path, selected)?;
+                        Some((self.apply_at(key, path, true).0, value))
+                    })
+                    .collect();
+                let kept_any = !pairs.is_empty();
+                (Value::Dict(pairs), kept_any)
+            }
+            Value::Constructor(name, ref args) => {
+                let (mut kept_any, mut hid_any) = (false, false);
+                let mut args: Vec<_> = args
+                    .iter()
+                    .filter_map(|arg| match *arg {
+                        Arg::Arg(ref value) => {
+                            let (value, kept) = self.apply_at(value, path, selected);
+                            kept_any |= kept;
+                            Some(Arg::Arg(value))
+                        }
+                        Arg::Kwarg(key, ref value) => {
+                            match self.apply_field(Cow::Borrowed(key), value, path, selected) {
+                                Some(value) if !self.is_hidden(&value) => {
+                                    kept_any = true;
+                                    Some(Arg::Kwarg(key, value))
+                                }
+                                _ => {
+                                    hid_any = true;
+                                    None
+                                }
+                            }
+                        }
+                    })
+                    .collect();
+                if hid_any && !self.hide_silently {
+                    args.push(Arg::Arg(Value::Symbol(HIDDEN_MARKER)));
+                }
+                (Value::Constructor(name, args), kept_any)
+            }
+            _ => (value.clone(), false),
+        }
+    }
+
+    fn apply_all<'a>(
+        &self,
+        xs: &[Value<'a>],
+        path: &mut Vec<Cow<'a, str>>,
+        selected: bool,
+    ) -> (Vec<Value<'a>>, bool) {
+        let mut kept_any = false;
+        let xs = xs
+            .iter()
+            .map(|x| {
+                let (x, kept) = self.apply_at(x, path, selected);
+                kept_any |= kept;
+                x
+            })
+            .collect();
+        (xs, kept_any)
+    }
+
+    /// Applies this filter to the `value` of the field `key` inside `path`, or returns `None` if
+    /// the field is left out.
+    fn apply_field<'a>(
+        &self,
+        key: Cow<'a, str>,
+        value: &Value<'a>,
+        path: &mut Vec<Cow<'a, str>>,
+        selected: bool,
+    ) -> Option<Value<'a>> {
+        path.push(key);
+        let matches = |patterns: &[FieldPattern], ignore_case| {
+            patterns
+                .iter()
+                .any(|pattern| pattern.matches(path, ignore_case))
+        };
+        let selected = selected || matches(&self.only, false);
+        let value = if matches(&self.exclude, false) {
+            None
+        } else if matches(&self.redact, true) {
+            if selected {
+                Some(redacted(value))
+            } else {
+                None
+            }
+        } else {
+            let (value, kept_any) = self.apply_at(value, path, selected);
+            if selected || kept_any {
+                Some(value)
+            } else {
+                None
+            }
+        };
+        path.pop();
+        value
+    }
+}
@@ -0,0 +1,111 @@
+use std::io::{self, Write};
+
+use pretty::{Render, RenderAnnotated};
+use termion::style;
+
+use crate::value::theme::Theme;
+
+/// The kinds of text that `m-o` can highlight in its output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Highlight {
+    /// The name in `Dog(...)`.
+    Constructor,
+    /// The name in `name=...`.
+    Kwarg,
+    Str,
+    Int,
+    Float,
+    /// `True`, `False` and `None`.
+    Bool,
+    Symbol,
+    /// Brackets, commas, colons and equals signs.
+    Punctuation,
+    /// Part of a value that was removed, as shown in a diff.
+    Deleted,
+    /// Part of a value that was added, as shown in a diff.
+    Inserted,
+}
+
+impl Highlight {
+    fn is_diff(self) -> bool {
+        matches!(self, Highlight::Deleted | Highlight::Inserted)
+    }
+}
+
+/// Renders documents to an `io::Write`, using ANSI escape codes to style highlighted text
+/// according to a `Theme`.
+pub struct HighlightWriter<W> {
+    upstream: W,
+    /// The theme to style text with, or `None` to leave the output uncolored.
+    theme: Option<Theme>,
+    highlights: Vec<Highlight>,
+    /// The highlight whose escape codes were written last.
+    active: Option<Highlight>,
+}
+
+impl<W: Write> HighlightWriter<W> {
+    /// Creates a writer which styles text with `theme`, or leaves it uncolored if it's `None`.
+    pub fn with_theme(upstream: W, theme: Option<Theme>) -> Self {
+        HighlightWriter {
+            upstream,
+            theme,
+            highlights: Vec::new(),
+            active: None,
+        }
+    }
+
+    /// The highlight that applies to text written now. Within a diff, the changed parts are
+    /// highlighted as a whole, so their syntax highlighting doesn't hide that they changed.
+    fn current(&self) -> Option<Highlight> {
+        let mut highlights = self.highlights.iter().rev();
+        highlights
+            .clone()
+            .find(|highlight| highlight.is_diff())
+            .or_else(|| highlights.next())
+            .cloned()
+    }
+
+    fn restyle(&mut self) -> io::Result<()> {
+        let theme = match self.theme {
+            Some(ref theme) => theme,
+            None => return Ok(()),
+        };
+        let current = self.current();
+        if current == self.active {
+            return Ok(());
+        }
+
+        if self.active.is_some() {
+            write!(self.upstream, "{}", style::Reset)?;
+        }
+        if let Some(highlight) = current {
+            write!(self.upstream, "{}", theme.style(highlight).escape_code())?;
+        }
+        self.active = current;
+        Ok(())
+    }
+}
+
+impl<W: Write> Render for HighlightWriter<W> {
+    type Error = io::Error;
+
+    fn write_str(&mut self, s: &str) -> io::Result<usize> {
+        self.upstream.write(s.as_bytes())
+    }
+
+    fn write_str_all(&mut self, s: &str) -> io::Result<()> {
+        self.upstream.write_all(s.as_bytes())
+    }
+}
+
+impl<W: Write> RenderAnnotated<Highlight> for HighlightWriter<W> {
+    fn push_annotation(&mut self, highlight: &Highlight) -> io::Result<()> {
+        self.highlights.push(*highlight);
+        self.restyle()
+    }
+
+    fn pop_annotation(&mut self) -> io::Result<()> {
+        self.highlights.pop();
+        self.restyle()
+    }
+}
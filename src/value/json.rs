@@ -0,0 +1,240 @@
+use std::fmt::{self, Write};
+
+use pretty::{BoxDoc, Doc};
+
+use super::{Arg, Value};
+use crate::value::highlight::Highlight;
+use crate::value::print::{float_to_string, highlighted, to_one_line, PrintOptions};
+use crate::value::quotes::decode;
+
+/// The key of the positional args of constructors converted to JSON objects.
+pub const ARGS_FIELD: &str = "__args__";
+
+/// How values are converted to JSON.
+#[derive(Debug, Clone)]
+pub struct JsonOptions {
+    /// Print everything on one line, without spaces.
+    pub compact: bool,
+    /// The key of the constructor names of constructors converted to JSON objects.
+    pub type_field: String,
+}
+
+impl Default for JsonOptions {
+    fn default() -> Self {
+        JsonOptions {
+            compact: false,
+            type_field: "__type__".to_string(),
+        }
+    }
+}
+
+/// A value which can't be converted to JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonError {
+    pub message: String,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+type JsonDoc<'a> = Doc<'a, BoxDoc<'a, Highlight>, Highlight>;
+
+/// The kwarg of the constructor `name` whose key is the key of its name, `type_field`, or the
+/// key of its positional args, which would be duplicate keys once it's converted to an object or
+/// mapping. Returns the message of the error about it.
+pub(crate) fn colliding_kwarg(
+    name: &str,
+    args: &[Arg],
+    type_field: Option<&str>,
+) -> Option<String> {
+    let has_positional = args.iter().any(|arg| matches!(arg, Arg::Arg(_)));
+    args.iter().find_map(|arg| match *arg {
+        Arg::Kwarg(key, _) if Some(key) == type_field => Some(format!(
+            "the kwarg `{}` of `{}` has the same key as the constructor name, which can be put \
+             under another key with `--type-field`",
+            key, name
+        )),
+        Arg::Kwarg(key, _) if key == ARGS_FIELD && has_positional => Some(format!(
+            "the kwarg `{}` of `{}` has the same key as the positional args",
+            key, name
+        )),
+        _ => None,
+    })
+}
+
+/// `text` as a JSON string literal, including its quotes.
+pub(crate) fn json_string(text: &str) -> String {
+    let mut literal = String::with_capacity(text.len() + 2);
+    literal.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            '\x08' => literal.push_str("\\b"),
+            '\x0c' => literal.push_str("\\f"),
+            c if c.is_control() => {
+                let _ = write!(literal, "\\u{:04x}", c as u32);
+            }
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// A float as a JSON number. JSON has no infinities or NaN, so they become `null` like in
+/// JavaScript's `JSON.stringify`.
+fn json_float<'a>(x: f64) -> JsonDoc<'a> {
+    if x.is_finite() {
+        highlighted(float_to_string(x), Highlight::Float)
+    } else {
+        highlighted("null", Highlight::Bool)
+    }
+}
+
+impl<'a> Value<'a> {
+    /// The JSON object key for this dict key. Like in Python's `json` module, strings are decoded
+    /// and `None`, `True`, `False` and numbers are written as in JSON. Other values are written as
+    /// `m-o` prints them.
+    fn json_key(&self) -> String {
+        match *self {
+            Value::Str(ref raw) => decode(raw),
+            Value::Symbol("None") => "null".to_string(),
+            Value::Bool(x) => x.to_string(),
+            Value::Int(x) => x.to_string(),
+            Value::Float(x) => to_one_line(&json_float(x)),
+            _ => to_one_line(&self.to_doc(&PrintOptions::default())),
+        }
+    }
+
+    /// Converts this value to JSON. Dicts and constructors become objects, with the name of a
+    /// constructor under `json.type_field` and its positional args in an array under `__args__`.
+    /// Tuples, lists and sets become arrays, `None` becomes `null` and other symbols become
+    /// strings. Constructors with kwargs called like either of those keys can't be converted.
+    pub fn to_json_doc(
+        &self,
+        options: &PrintOptions,
+        json: &JsonOptions,
+    ) -> Result<JsonDoc<'a>, JsonError> {
+        Ok(match *self {
+            Value::Bool(x) => highlighted(x.to_string(), Highlight::Bool),
+            Value::Str(ref raw) => highlighted(json_string(&decode(raw)), Highlight::Str),
+            Value::Int(x) => highlighted(x.to_string(), Highlight::Int),
+            Value::Float(x) => json_float(x),
+            Value::Symbol("None") => highlighted("null", Highlight::Bool),
+            Value::Symbol(symbol) => highlighted(json_string(symbol), Highlight::Symbol),
+            Value::Tuple(ref xs) | Value::List(ref xs) => json_array(xs.iter(), options, json)?,
+            Value::Set(ref xs) => {
+                let mut xs: Vec<_> = xs.iter().collect();
+                if options.sort_sets {
+                    xs.sort_by(|x, y| x.total_cmp(y));
+                }
+                json_array(xs.into_iter(), options, json)?
+            }
+            Value::Dict(ref pairs) => {
+                let mut pairs: Vec<_> = pairs.iter().collect();
+                if options.sort_dicts {
+                    pairs.sort_by(|(k1, _), (k2, _)| k1.total_cmp(k2));
+                }
+                let members = pairs
+                    .into_iter()
+                    .map(|(key, value)| {
+                        Ok(json_member(
+                            key.json_key(),
+                            value.to_json_doc(options, json)?,
+                            json,
+                        ))
+                    })
+                    .collect::<Result<_, _>>()?;
+                json_seq("{", members, "}", options, json)
+            }
+            Value::Constructor(name, ref args) => {
+                if let Some(message) = colliding_kwarg(name, args, Some(&json.type_field)) {
+                    return Err(JsonError { message });
+                }
+                let positional: Vec<_> = args
+                    .iter()
+                    .filter_map(|arg| match *arg {
+                        Arg::Arg(ref value) => Some(value),
+                        Arg::Kwarg(..) => None,
+                    })
+                    .collect();
+                let mut members = vec![json_member(
+                    json.type_field.clone(),
+                    highlighted(json_string(name), Highlight::Constructor),
+                    json,
+                )];
+                if !positional.is_empty() {
+                    members.push(json_member(
+                        ARGS_FIELD.to_string(),
+                        json_array(positional.into_iter(), options, json)?,
+                        json,
+                    ));
+                }
+                for arg in args {
+                    if let Arg::Kwarg(key, ref value) = *arg {
+                        members.push(json_member(
+                            key.to_string(),
+                            value.to_json_doc(options, json)?,
+                            json,
+                        ));
+                    }
+                }
+                json_seq("{", members, "}", options, json)
+            }
+        })
+    }
+}
+
+fn json_array<'tmp, 'a: 'tmp>(
+    xs: impl Iterator<Item = &'tmp Value<'a>>,
+    options: &PrintOptions,
+    json: &JsonOptions,
+) -> Result<JsonDoc<'a>, JsonError> {
+    let items = xs
+        .map(|x| x.to_json_doc(options, json))
+        .collect::<Result<_, _>>()?;
+    Ok(json_seq("[", items, "]", options, json))
+}
+
+/// The member `key: value` of an object.
+fn json_member<'a>(key: String, value: JsonDoc<'a>, json: &JsonOptions) -> JsonDoc<'a> {
+    let colon = if json.compact { ":" } else { ": " };
+    highlighted(json_string(&key), Highlight::Kwarg)
+        .append(highlighted(colon, Highlight::Punctuation))
+        .append(value)
+}
+
+/// An array or object of `items`, which are each put on their own line unless `json.compact`.
+fn json_seq<'a>(
+    open: &'static str,
+    items: Vec<JsonDoc<'a>>,
+    close: &'static str,
+    options: &PrintOptions,
+    json: &JsonOptions,
+) -> JsonDoc<'a> {
+    let open = highlighted(open, Highlight::Punctuation);
+    let close = highlighted(close, Highlight::Punctuation);
+    if items.is_empty() {
+        return open.append(close);
+    }
+
+    let comma = highlighted(",", Highlight::Punctuation);
+    if json.compact {
+        open.append(Doc::intersperse(items, comma)).append(close)
+    } else {
+        open.append(
+            Doc::newline()
+                .append(Doc::intersperse(items, comma.append(Doc::newline())))
+                .nest(options.indent),
+        )
+        .append(Doc::newline())
+        .append(close)
+    }
+}
@@ -1,10 +1,26 @@
+use std::borrow::Cow;
+
+pub mod ast;
+pub mod diff;
+pub mod filter;
+pub mod highlight;
+pub mod json;
+pub mod order;
 pub mod parse;
 pub mod print;
+pub mod quotes;
+pub mod table;
+pub mod theme;
+pub mod toml;
+pub mod tree;
+pub mod yaml;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value<'a> {
     Bool(bool),
-    Str(&'a str),
+    /// A string literal, quotes included. It's borrowed from the input, except for the literals
+    /// which replace strings redacted by `--redact`.
+    Str(Cow<'a, str>),
     Int(i64),
     Float(f64),
     Tuple(Vec<Value<'a>>),
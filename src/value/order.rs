@@ -0,0 +1,91 @@
+use std::cmp::Ordering;
+
+use super::{Arg, Value};
+
+impl<'a> Value<'a> {
+    /// The position of this kind of value in the ordering of `total_cmp`. Numbers of different
+    /// types are ordered by their values first.
+    fn kind_rank(&self) -> u8 {
+        match self {
+            Value::Symbol(_) => 0,
+            Value::Bool(_) | Value::Int(_) | Value::Float(_) => 1,
+            Value::Str(_) => 2,
+            Value::Tuple(_) => 3,
+            Value::List(_) => 4,
+            Value::Set(_) => 5,
+            Value::Dict(_) => 6,
+            Value::Constructor(..) => 7,
+        }
+    }
+
+    /// The value of a number, for comparing numbers of different types.
+    fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Value::Bool(x) => Some(if x { 1.0 } else { 0.0 }),
+            Value::Int(x) => Some(x as f64),
+            Value::Float(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Compares values of any types, so that collections can be sorted for stable output.
+    /// Numbers are ordered by value like in Python, even across `bool`, `int` and `float`, and
+    /// strings by their contents regardless of their quotes. Otherwise values of different kinds
+    /// are ordered by kind, and collections and constructors are ordered lexicographically.
+    pub fn total_cmp(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Int(x), Value::Int(y)) => x.cmp(y),
+            (Value::Str(x), Value::Str(y)) => {
+                str_contents(x).cmp(str_contents(y)).then_with(|| x.cmp(y))
+            }
+            (Value::Symbol(x), Value::Symbol(y)) => x.cmp(y),
+            (Value::Tuple(xs), Value::Tuple(ys))
+            | (Value::List(xs), Value::List(ys))
+            | (Value::Set(xs), Value::Set(ys)) => cmp_seqs(xs, ys, Value::total_cmp),
+            (Value::Dict(xs), Value::Dict(ys)) => cmp_seqs(xs, ys, |(k1, v1), (k2, v2)| {
+                k1.total_cmp(k2).then_with(|| v1.total_cmp(v2))
+            }),
+            (Value::Constructor(x, xs), Value::Constructor(y, ys)) => {
+                x.cmp(y).then_with(|| cmp_seqs(xs, ys, cmp_args))
+            }
+            _ => match (self.as_f64(), other.as_f64()) {
+                (Some(x), Some(y)) => x
+                    .total_cmp(&y)
+                    .then_with(|| number_rank(self).cmp(&number_rank(other))),
+                _ => self.kind_rank().cmp(&other.kind_rank()),
+            },
+        }
+    }
+}
+
+/// Orders `True`, `1` and `1.0`, which are equal as numbers.
+fn number_rank(value: &Value) -> u8 {
+    match value {
+        Value::Bool(_) => 0,
+        Value::Int(_) => 1,
+        _ => 2,
+    }
+}
+
+/// The text of the string literal `raw` between its quotes.
+fn str_contents(raw: &str) -> &str {
+    &raw[1..raw.len() - 1]
+}
+
+fn cmp_seqs<T>(xs: &[T], ys: &[T], cmp: impl Fn(&T, &T) -> Ordering) -> Ordering {
+    xs.iter()
+        .zip(ys)
+        .map(|(x, y)| cmp(x, y))
+        .find(|&ordering| ordering != Ordering::Equal)
+        .unwrap_or_else(|| xs.len().cmp(&ys.len()))
+}
+
+/// Orders positional args before kwargs, and kwargs by name.
+fn cmp_args(x: &Arg, y: &Arg) -> Ordering {
+    match (x, y) {
+        (Arg::Arg(x), Arg::Arg(y)) => x.total_cmp(y),
+        (Arg::Arg(_), Arg::Kwarg(..)) => Ordering::Less,
+        (Arg::Kwarg(..), Arg::Arg(_)) => Ordering::Greater,
+        (Arg::Kwarg(k1, v1), Arg::Kwarg(k2, v2)) => k1.cmp(k2).then_with(|| v1.total_cmp(v2)),
+    }
+}
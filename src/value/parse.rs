@@ -4,7 +4,7 @@ use nom::{
     branch::alt,
     bytes::complete::{escaped, is_not, tag},
     character::complete::{char, digit1, multispace0, one_of},
-    combinator::{map, not, opt, recognize},
+    combinator::{all_consuming, map, not, opt, recognize},
     multi::separated_list,
     number::complete::double,
     re_find,
@@ -16,14 +16,14 @@ use super::Value;
 use crate::value::Arg;
 use crate::value::Arg::Kwarg;
 
-pub fn parse_bool(input: &str) -> IResult<&str, Value> {
+pub fn parse_bool(input: &str) -> IResult<&str, Value<'_>> {
     alt((
         map(tag("True"), |_| Value::Bool(true)),
         map(tag("False"), |_| Value::Bool(false)),
     ))(input)
 }
 
-pub fn parse_str(input: &str) -> IResult<&str, Value> {
+pub fn parse_str(input: &str) -> IResult<&str, Value<'_>> {
     // See: https://python-reference.readthedocs.io/en/latest/docs/str/escapes.html
     //
     // \a           ASCII bell
@@ -43,18 +43,28 @@ pub fn parse_str(input: &str) -> IResult<&str, Value> {
 
     let single_quoted = recognize(delimited(
         char('\''),
-        escaped(is_not(r#"'\"#), '\\', one_of(single_quoted_str_escape)),
+        opt(escaped(
+            is_not(r#"'\"#),
+            '\\',
+            one_of(single_quoted_str_escape),
+        )),
         char('\''),
     ));
     let double_quoted = recognize(delimited(
         char('"'),
-        escaped(is_not(r#""\"#), '\\', one_of(double_quoted_str_escape)),
+        opt(escaped(
+            is_not(r#""\"#),
+            '\\',
+            one_of(double_quoted_str_escape),
+        )),
         char('"'),
     ));
-    map(alt((single_quoted, double_quoted)), Value::Str)(input)
+    map(alt((single_quoted, double_quoted)), |raw: &str| {
+        Value::Str(raw.into())
+    })(input)
 }
 
-pub fn parse_int(input: &str) -> IResult<&str, Value> {
+pub fn parse_int(input: &str) -> IResult<&str, Value<'_>> {
     map(
         tuple((opt(tag("-")), terminated(digit1, not(tag("."))))),
         |(sign, s): (Option<&str>, &str)| {
@@ -67,10 +77,35 @@ pub fn parse_int(input: &str) -> IResult<&str, Value> {
     )(input)
 }
 
-pub fn parse_float(input: &str) -> IResult<&str, Value> {
+pub fn parse_float(input: &str) -> IResult<&str, Value<'_>> {
     map(double, Value::Float)(input)
 }
 
+/// Matches the `open` bracket of a collection along with any whitespace after it.
+fn open_bracket<'a>(open: char) -> impl Fn(&'a str) -> IResult<&'a str, char> {
+    terminated(char(open), multispace0)
+}
+
+/// Matches the comma-separated items of a collection, allowing a trailing comma after the last
+/// one, as in the multi-line layouts that `m-o` prints. An empty collection can't have one.
+fn items<'a, T>(
+    item: impl Fn(&'a str) -> IResult<&'a str, T>,
+) -> impl Fn(&'a str) -> IResult<&'a str, Vec<T>> {
+    move |input: &'a str| {
+        let (input, items) = separated_list(comma_space, &item)(input)?;
+        if items.is_empty() {
+            return Ok((input, items));
+        }
+        let (input, _) = opt(preceded(multispace0, char(',')))(input)?;
+        Ok((input, items))
+    }
+}
+
+/// Matches the `close` bracket of a collection along with any whitespace before it.
+fn close_bracket<'a>(close: char) -> impl Fn(&'a str) -> IResult<&'a str, char> {
+    preceded(multispace0, char(close))
+}
+
 fn parse_seq<'a>(
     open: char,
     f: impl Fn(Vec<Value<'a>>) -> Value<'a>,
@@ -78,33 +113,31 @@ fn parse_seq<'a>(
 ) -> impl Fn(&'a str) -> IResult<&'a str, Value<'a>> {
     move |input: &'a str| -> IResult<&'a str, Value> {
         map(
-            delimited(
-                char(open),
-                separated_list(comma_space, parse_value),
-                char(close),
-            ),
+            delimited(open_bracket(open), items(parse_value), close_bracket(close)),
             &f,
         )(input)
     }
 }
 
-pub fn parse_list(input: &str) -> IResult<&str, Value> {
+pub fn parse_list(input: &str) -> IResult<&str, Value<'_>> {
     parse_seq('[', Value::List, ']')(input)
 }
 
-pub fn parse_tuple(input: &str) -> IResult<&str, Value> {
+pub fn parse_tuple(input: &str) -> IResult<&str, Value<'_>> {
     parse_seq('(', Value::Tuple, ')')(input)
 }
 
-pub fn parse_set(input: &str) -> IResult<&str, Value> {
+pub fn parse_set(input: &str) -> IResult<&str, Value<'_>> {
     parse_seq('{', Value::Set, '}')(input)
 }
 
+/// Matches the `:` of a dict entry along with any whitespace around it, which includes the
+/// padding of the aligned layouts that `m-o` prints.
 fn colon_space(input: &str) -> IResult<&str, ()> {
-    map(preceded(char(':'), multispace0), |_| ())(input)
+    map(delimited(multispace0, char(':'), multispace0), |_| ())(input)
 }
 
-fn parse_dict_key_value(input: &str) -> IResult<&str, (Value, Value)> {
+fn parse_dict_key_value(input: &str) -> IResult<&str, (Value<'_>, Value<'_>)> {
     tuple((parse_value, preceded(colon_space, parse_value)))(input)
 }
 
@@ -112,12 +145,12 @@ fn comma_space(input: &str) -> IResult<&str, ()> {
     map(preceded(char(','), multispace0), |_| ())(input)
 }
 
-pub fn parse_dict(input: &str) -> IResult<&str, Value> {
+pub fn parse_dict(input: &str) -> IResult<&str, Value<'_>> {
     map(
         delimited(
-            char('{'),
-            separated_list(comma_space, parse_dict_key_value),
-            char('}'),
+            open_bracket('{'),
+            items(parse_dict_key_value),
+            close_bracket('}'),
         ),
         Value::Dict,
     )(input)
@@ -130,31 +163,37 @@ fn identifier(input: &str) -> IResult<&str, &str> {
     )
 }
 
-pub fn parse_symbol(input: &str) -> IResult<&str, Value> {
+pub fn parse_symbol(input: &str) -> IResult<&str, Value<'_>> {
     map(identifier, Value::Symbol)(input)
 }
 
-fn parse_arg(input: &str) -> IResult<&str, Arg> {
+/// Matches the `=` of a kwarg along with any whitespace before it, which is the padding of the
+/// aligned layouts that `m-o` prints.
+fn equals(input: &str) -> IResult<&str, ()> {
+    map(preceded(multispace0, char('=')), |_| ())(input)
+}
+
+fn parse_arg(input: &str) -> IResult<&str, Arg<'_>> {
     alt((
         map(
-            tuple((identifier, preceded(char('='), parse_value))),
+            tuple((identifier, preceded(equals, parse_value))),
             |(ident, value)| Kwarg(ident, value),
         ),
         map(parse_value, Arg::Arg),
     ))(input)
 }
 
-pub fn parse_constructor(input: &str) -> IResult<&str, Value> {
+pub fn parse_constructor(input: &str) -> IResult<&str, Value<'_>> {
     map(
         tuple((
             identifier,
-            delimited(char('('), separated_list(comma_space, parse_arg), char(')')),
+            delimited(open_bracket('('), items(parse_arg), close_bracket(')')),
         )),
         |(name, kwargs)| Value::Constructor(name, kwargs),
     )(input)
 }
 
-pub fn parse_value(input: &str) -> IResult<&str, Value> {
+pub fn parse_value(input: &str) -> IResult<&str, Value<'_>> {
     alt((
         parse_int,
         parse_float, // Appears after int parser because f64 is superset of i64
@@ -169,6 +208,12 @@ pub fn parse_value(input: &str) -> IResult<&str, Value> {
     ))(input)
 }
 
+/// Parses all of `input` as a single value, failing if anything but whitespace is left after
+/// it, unlike `Value::try_from`, which ignores the rest of the input.
+pub fn parse_complete_value(input: &str) -> IResult<&str, Value<'_>> {
+    all_consuming(terminated(parse_value, multispace0))(input)
+}
+
 impl<'a> TryFrom<&'a str> for Value<'a> {
     type Error = nom::Err<(&'a str, nom::error::ErrorKind)>;
 
@@ -1,12 +1,45 @@
+use std::borrow::Cow;
 use std::fmt;
+use std::iter;
 
 use super::Value;
+use crate::value::highlight::Highlight;
+use crate::value::quotes::{self, QuoteStyle};
 use crate::value::Arg;
 use pretty::{BoxDoc, Doc};
 
 pub struct PrintOptions {
     pub indent: usize,
     pub columns: usize,
+    /// Put a comma after the last item of a collection when it's broken over several lines, like
+    /// `black` does.
+    pub trailing_comma: bool,
+    pub quotes: QuoteStyle,
+    /// Print dict entries sorted by key, instead of in their original order.
+    pub sort_dicts: bool,
+    /// Print set members sorted by value, instead of in their original order.
+    pub sort_sets: bool,
+    /// Collapse collections and constructors nested deeper than this into a summary like
+    /// `[...12 items]` or `Dog(...)`. The top-level value is at depth 0.
+    pub max_depth: Option<usize>,
+    /// Only print this many items of long lists, tuples, sets and dicts, with a marker like
+    /// `... 9,980 more ...` in place of the rest.
+    pub max_items: Option<usize>,
+    /// With `max_items`, also print that many items from the end of long collections, eliding
+    /// the ones in the middle.
+    pub keep_last_items: bool,
+    /// Only print this many characters of long strings, with a marker like `... (49,990 more
+    /// chars)` after them.
+    pub max_string_length: Option<usize>,
+    /// Split strings which don't fit on their line into implicitly concatenated chunks, wrapped
+    /// in parentheses.
+    pub wrap_strings: bool,
+    /// Align the `=` of kwargs and the `:` of dict entries into a column when a constructor or
+    /// dict is broken over several lines.
+    pub align: bool,
+    /// Print lists and tuples of calls of the same constructor with the same kwargs as tables,
+    /// with a column for each kwarg.
+    pub tables: bool,
 }
 
 impl Default for PrintOptions {
@@ -14,93 +47,404 @@ impl Default for PrintOptions {
         PrintOptions {
             indent: 4,
             columns: 80,
+            trailing_comma: false,
+            quotes: QuoteStyle::Preserve,
+            sort_dicts: false,
+            sort_sets: false,
+            max_depth: None,
+            max_items: None,
+            keep_last_items: false,
+            max_string_length: None,
+            wrap_strings: false,
+            align: false,
+            tables: false,
         }
     }
 }
 
+/// Formats `n` with commas between groups of thousands, like `9,980`.
+fn group_digits(n: usize) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Formats a float so that it's read back as a float: unlike `Display`, `Debug` keeps the `.0`
+/// of whole numbers, but it doesn't add one before an exponent, as in `1e-7`.
+pub(crate) fn float_to_string(x: f64) -> String {
+    let number = format!("{:?}", x);
+    match number.split_once('e') {
+        Some((mantissa, exponent)) if !mantissa.contains('.') => {
+            format!("{}.0e{}", mantissa, exponent)
+        }
+        _ => number,
+    }
+}
+
+/// Splits the items `xs` of a collection longer than `options.max_items` into the ones printed
+/// before the elided ones, the number elided, and the ones printed after them. Returns `None` if
+/// all of them are printed.
+pub(crate) fn elide<'a, T>(
+    xs: &'a [T],
+    options: &PrintOptions,
+) -> Option<(&'a [T], usize, &'a [T])> {
+    let (head, tail) = match options.max_items {
+        Some(max_items) if options.keep_last_items && xs.len() > max_items.saturating_mul(2) => {
+            (&xs[..max_items], &xs[xs.len() - max_items..])
+        }
+        Some(max_items) if !options.keep_last_items && xs.len() > max_items => {
+            (&xs[..max_items], &xs[xs.len()..])
+        }
+        _ => return None,
+    };
+    Some((head, xs.len() - head.len() - tail.len(), tail))
+}
+
+/// The marker which replaces `elided` items of a long collection.
+pub(crate) fn elided_marker<'a>(elided: usize) -> Doc<'a, BoxDoc<'a, Highlight>, Highlight> {
+    highlighted(
+        format!("... {} more ...", group_digits(elided)),
+        Highlight::Punctuation,
+    )
+}
+
+/// Renders `doc` without breaking any of its groups over several lines.
+pub(crate) fn to_one_line(doc: &Doc<BoxDoc<Highlight>, Highlight>) -> String {
+    // Not `usize::MAX`, which `pretty` treats as a negative width.
+    doc.pretty(isize::MAX as usize).to_string()
+}
+
+/// The text `text`, highlighted as `highlight`.
+pub(crate) fn highlighted<'a, S>(
+    text: S,
+    highlight: Highlight,
+) -> Doc<'a, BoxDoc<'a, Highlight>, Highlight>
+where
+    S: Into<Cow<'a, str>>,
+{
+    Doc::text(text).annotate(highlight)
+}
+
+/// The longest string literal, including its quotes, which counts as short enough to be packed
+/// into a fill layout.
+const MAX_FILL_STR_LEN: usize = 20;
+
 impl<'value> Value<'value> {
-    fn seq_to_doc<'iter, I>(
+    /// Whether `xs` are all numbers or all short strings, which are easier to read packed into as
+    /// few lines as possible than one per line.
+    fn fills(xs: &[Value]) -> bool {
+        let is_number = |x: &Value| matches!(x, Value::Int(_) | Value::Float(_));
+        let is_short_str = |x: &Value| match x {
+            Value::Str(raw) => raw.chars().count() <= MAX_FILL_STR_LEN,
+            _ => false,
+        };
+        !xs.is_empty() && (xs.iter().all(is_number) || xs.iter().all(is_short_str))
+    }
+
+    /// Lays out the items `xs` between the `open` and `close` brackets, all on one line if they
+    /// fit. Otherwise they're put one per line, or with `fill`, as many per line as fit.
+    pub(crate) fn seq_to_doc<'iter, I>(
         open: &'static str,
         xs: I,
         close: &'static str,
+        fill: bool,
         options: &PrintOptions,
-    ) -> Doc<'value, BoxDoc<'value, ()>>
+    ) -> Doc<'value, BoxDoc<'value, Highlight>, Highlight>
     where
-        I: Iterator<Item = Doc<'value, BoxDoc<'value, ()>>>,
+        I: Iterator<Item = Doc<'value, BoxDoc<'value, Highlight>, Highlight>>,
         'value: 'iter,
     {
-        Doc::text(open)
+        let mut xs = xs.peekable();
+        let trailing_comma = if options.trailing_comma && xs.peek().is_some() {
+            highlighted(",", Highlight::Punctuation).flat_alt(Doc::nil())
+        } else {
+            Doc::nil()
+        };
+
+        let space = if fill {
+            // Not `Doc::space()`, which doesn't count towards the width of the line when flat.
+            Doc::newline().flat_alt(Doc::text(" ")).group()
+        } else {
+            Doc::space()
+        };
+        let separator = highlighted(",", Highlight::Punctuation).append(space);
+        highlighted(open, Highlight::Punctuation)
             .append(Doc::newline().flat_alt(Doc::nil()))
             .nest(options.indent)
-            .append(Doc::intersperse(xs, Doc::text(",").append(Doc::space())).nest(options.indent))
+            .append(Doc::intersperse(xs, separator).nest(options.indent))
+            .append(trailing_comma)
             .append(Doc::newline().flat_alt(Doc::nil()))
-            .append(Doc::text(close))
+            .append(highlighted(close, Highlight::Punctuation))
             .group()
     }
 
+    fn str_to_doc(
+        raw: Cow<'value, str>,
+        options: &PrintOptions,
+        depth: usize,
+    ) -> Doc<'value, BoxDoc<'value, Highlight>, Highlight> {
+        let mut literal = match raw {
+            Cow::Borrowed(raw) => quotes::requote(raw, options.quotes),
+            Cow::Owned(raw) => Cow::Owned(quotes::requote(&raw, options.quotes).into_owned()),
+        };
+        let mut marker = Doc::nil();
+        if let Some((truncated, elided)) = options
+            .max_string_length
+            .and_then(|max_chars| quotes::truncate(&literal, max_chars))
+        {
+            literal = Cow::Owned(truncated);
+            marker = highlighted(
+                format!("... ({} more chars)", group_digits(elided)),
+                Highlight::Punctuation,
+            );
+        }
+
+        if !options.wrap_strings {
+            return highlighted(literal, Highlight::Str).append(marker);
+        }
+
+        // The chunks are indented one level deeper than the string itself.
+        let width = options.columns.saturating_sub((depth + 1) * options.indent);
+        let chunks = quotes::split(&literal, width);
+        if chunks.len() == 1 {
+            return highlighted(literal, Highlight::Str).append(marker);
+        }
+
+        let chunks = chunks
+            .into_iter()
+            .map(|chunk| highlighted(chunk, Highlight::Str));
+        let broken = highlighted("(", Highlight::Punctuation)
+            .append(Doc::newline())
+            .append(Doc::intersperse(chunks, Doc::newline()))
+            .nest(options.indent)
+            .append(Doc::newline())
+            .append(highlighted(")", Highlight::Punctuation));
+        broken
+            .flat_alt(highlighted(literal, Highlight::Str))
+            .group()
+            .append(marker)
+    }
+
+    /// The docs for the items `xs` of a list, tuple, set or dict. If there are more than
+    /// `options.max_items`, the ones which aren't printed are replaced by a `... N more ...`
+    /// marker.
+    fn items_to_docs<'tmp, T>(
+        xs: &'tmp [T],
+        options: &PrintOptions,
+        to_doc: impl Fn(&'tmp T) -> Doc<'value, BoxDoc<'value, Highlight>, Highlight>,
+    ) -> Vec<Doc<'value, BoxDoc<'value, Highlight>, Highlight>> {
+        match elide(xs, options) {
+            Some((head, elided, tail)) => head
+                .iter()
+                .map(&to_doc)
+                .chain(iter::once(elided_marker(elided)))
+                .chain(tail.iter().map(&to_doc))
+                .collect(),
+            None => xs.iter().map(to_doc).collect(),
+        }
+    }
+
     fn dictionary_to_doc<'tmp>(
         pairs: &'tmp [(Value<'value>, Value<'value>)],
         options: &PrintOptions,
-    ) -> Doc<'value, BoxDoc<'value, ()>>
+        depth: usize,
+    ) -> Doc<'value, BoxDoc<'value, Highlight>, Highlight>
     where
         'value: 'tmp,
     {
-        Self::seq_to_doc(
-            "{",
-            pairs.iter().map(|(key, value)| {
-                key.to_doc(options)
-                    .append(Doc::text(": "))
-                    .append(value.to_doc(options))
-            }),
-            "}",
-            options,
-        )
-        .group()
+        let mut pairs: Vec<_> = pairs.iter().collect();
+        if options.sort_dicts {
+            pairs.sort_by(|(k1, _), (k2, _)| k1.total_cmp(k2));
+        }
+
+        let keys: Vec<_> = pairs
+            .iter()
+            .map(|(key, _)| {
+                let key = key.to_doc_at_depth(options, depth + 1);
+                let width = to_one_line(&key).chars().count();
+                (key, width)
+            })
+            .collect();
+        let key_width = keys.iter().map(|&(_, width)| width).max().unwrap_or(0);
+
+        let entries: Vec<_> = keys.into_iter().zip(pairs).collect();
+        let pairs = Self::items_to_docs(&entries, options, |((key, width), (_, value))| {
+            key.clone()
+                .append(Self::alignment(*width, key_width, options))
+                .append(highlighted(":", Highlight::Punctuation))
+                .append(Doc::text(" "))
+                .append(value.to_doc_at_depth(options, depth + 1))
+        });
+        Self::seq_to_doc("{", pairs.into_iter(), "}", false, options).group()
     }
 
     fn constructor_to_doc<'tmp>(
         name: &'value str,
         args: &'tmp [Arg<'value>],
         options: &PrintOptions,
-    ) -> Doc<'value, BoxDoc<'value, ()>>
+        depth: usize,
+    ) -> Doc<'value, BoxDoc<'value, Highlight>, Highlight>
     where
         'value: 'tmp,
     {
-        Doc::text(name)
+        let key_width = args
+            .iter()
+            .filter_map(|arg| match arg {
+                Arg::Kwarg(key, _) => Some(key.chars().count()),
+                Arg::Arg(_) => None,
+            })
+            .max()
+            .unwrap_or(0);
+
+        highlighted(name, Highlight::Constructor)
             .append(Self::seq_to_doc(
                 "(",
                 args.iter().map(|arg| match arg {
-                    Arg::Arg(value) => value.to_doc(options),
-                    Arg::Kwarg(key, value) => Doc::text(*key)
-                        .append(Doc::text("="))
-                        .append(value.to_doc(options)),
+                    Arg::Arg(value) => value.to_doc_at_depth(options, depth + 1),
+                    Arg::Kwarg(key, value) => highlighted(*key, Highlight::Kwarg)
+                        .append(Self::alignment(key.chars().count(), key_width, options))
+                        .append(highlighted("=", Highlight::Punctuation))
+                        .append(value.to_doc_at_depth(options, depth + 1)),
                 }),
                 ")",
+                false,
                 options,
             ))
             .group()
     }
+
+    /// The padding after a kwarg or dict key `width` characters wide which aligns what follows it
+    /// with the widest one, `key_width` characters wide. It's only there when the constructor or
+    /// dict is broken over several lines, and `options.align` is set.
+    fn alignment(
+        width: usize,
+        key_width: usize,
+        options: &PrintOptions,
+    ) -> Doc<'value, BoxDoc<'value, Highlight>, Highlight> {
+        if !options.align || width >= key_width {
+            return Doc::nil();
+        }
+        Doc::text(" ".repeat(key_width - width)).flat_alt(Doc::nil())
+    }
+
+    /// A summary of a collection or constructor, like `[...12 items]` or `Dog(...)`, or `None`
+    /// if this value isn't one or is empty.
+    fn collapsed_doc(&self) -> Option<Doc<'value, BoxDoc<'value, Highlight>, Highlight>> {
+        let (open, len, noun, close) = match *self {
+            Value::Constructor(name, ref args) if !args.is_empty() => {
+                return Some(
+                    highlighted(name, Highlight::Constructor)
+                        .append(highlighted("(...)", Highlight::Punctuation)),
+                );
+            }
+            Value::List(ref xs) => ("[", xs.len(), "item", "]"),
+            Value::Tuple(ref xs) => ("(", xs.len(), "item", ")"),
+            Value::Set(ref xs) => ("{", xs.len(), "item", "}"),
+            Value::Dict(ref pairs) => ("{", pairs.len(), "key", "}"),
+            _ => return None,
+        };
+        if len == 0 {
+            return None;
+        }
+
+        let plural = if len == 1 { "" } else { "s" };
+        Some(highlighted(
+            format!(
+                "{}...{} {}{}{}",
+                open,
+                group_digits(len),
+                noun,
+                plural,
+                close
+            ),
+            Highlight::Punctuation,
+        ))
+    }
 }
 
 impl<'value> Value<'value> {
-    pub fn to_doc(&self, options: &PrintOptions) -> Doc<'value, BoxDoc<'value, ()>> {
+    pub fn to_doc(
+        &self,
+        options: &PrintOptions,
+    ) -> Doc<'value, BoxDoc<'value, Highlight>, Highlight> {
+        self.to_doc_at_depth(options, 0)
+    }
+
+    pub(crate) fn to_doc_at_depth(
+        &self,
+        options: &PrintOptions,
+        depth: usize,
+    ) -> Doc<'value, BoxDoc<'value, Highlight>, Highlight> {
+        if options
+            .max_depth
+            .is_some_and(|max_depth| depth >= max_depth)
+        {
+            if let Some(doc) = self.collapsed_doc() {
+                return doc;
+            }
+        }
+
         match *self {
-            Value::Int(x) => Doc::text(x.to_string()),
-            Value::Float(x) => Doc::text(x.to_string()),
-            Value::Bool(x) => Doc::text(if x { "True" } else { "False" }),
-            Value::Symbol(x) => Doc::text(x),
-            Value::Str(x) => Doc::text(x),
+            Value::Int(x) => highlighted(x.to_string(), Highlight::Int),
+            Value::Float(x) => highlighted(float_to_string(x), Highlight::Float),
+            Value::Bool(x) => highlighted(if x { "True" } else { "False" }, Highlight::Bool),
+            Value::Symbol("None") => highlighted("None", Highlight::Bool),
+            Value::Symbol(x) => highlighted(x, Highlight::Symbol),
+            Value::Str(ref x) => Self::str_to_doc(x.clone(), options, depth),
             Value::List(ref xs) => {
-                Self::seq_to_doc("[", xs.iter().map(|x| x.to_doc(options)), "]", options)
+                if options.tables {
+                    if let Some(doc) = Self::table_to_doc("[", xs, "]", options, depth) {
+                        return doc;
+                    }
+                }
+                let docs =
+                    Self::items_to_docs(xs, options, |x| x.to_doc_at_depth(options, depth + 1));
+                Self::seq_to_doc("[", docs.into_iter(), "]", Self::fills(xs), options)
+            }
+            Value::Tuple(ref xs) if xs.len() == 1 => {
+                // A tuple of one item always needs a comma. It's already there if the tuple is
+                // broken over several lines with trailing commas.
+                let comma = highlighted(",", Highlight::Punctuation);
+                let comma = if options.trailing_comma {
+                    Doc::nil().flat_alt(comma)
+                } else {
+                    comma
+                };
+                let docs = Self::items_to_docs(xs, options, |x| {
+                    x.to_doc_at_depth(options, depth + 1).append(comma.clone())
+                });
+                Self::seq_to_doc("(", docs.into_iter(), ")", false, options)
             }
             Value::Tuple(ref xs) => {
-                Self::seq_to_doc("(", xs.iter().map(|x| x.to_doc(options)), ")", options)
+                if options.tables {
+                    if let Some(doc) = Self::table_to_doc("(", xs, ")", options, depth) {
+                        return doc;
+                    }
+                }
+                let docs =
+                    Self::items_to_docs(xs, options, |x| x.to_doc_at_depth(options, depth + 1));
+                Self::seq_to_doc("(", docs.into_iter(), ")", Self::fills(xs), options)
             }
             Value::Set(ref xs) => {
-                Self::seq_to_doc("{", xs.iter().map(|x| x.to_doc(options)), "}", options)
+                let mut sorted: Vec<_> = xs.iter().collect();
+                if options.sort_sets {
+                    sorted.sort_by(|x, y| x.total_cmp(y));
+                }
+                let docs = Self::items_to_docs(&sorted, options, |x| {
+                    x.to_doc_at_depth(options, depth + 1)
+                });
+                Self::seq_to_doc("{", docs.into_iter(), "}", Self::fills(xs), options)
+            }
+            Value::Dict(ref pairs) => Self::dictionary_to_doc(pairs, options, depth),
+            Value::Constructor(name, ref args) => {
+                Self::constructor_to_doc(name, args, options, depth)
             }
-            Value::Dict(ref pairs) => Self::dictionary_to_doc(pairs, options),
-            Value::Constructor(name, ref args) => Self::constructor_to_doc(name, args, options),
         }
     }
 }
@@ -0,0 +1,212 @@
+use std::borrow::Cow;
+use std::str::FromStr;
+
+/// Which quotes strings are printed with.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum QuoteStyle {
+    /// Keep the quotes each string was written with.
+    #[default]
+    Preserve,
+    /// Prefer `'single'` quotes.
+    Single,
+    /// Prefer `"double"` quotes.
+    Double,
+}
+
+impl FromStr for QuoteStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "preserve" => Ok(QuoteStyle::Preserve),
+            "single" => Ok(QuoteStyle::Single),
+            "double" => Ok(QuoteStyle::Double),
+            _ => Err(format!("unknown quote style `{}`", s)),
+        }
+    }
+}
+
+/// Splits the body of a quoted string into its characters, with the backslashes removed from
+/// escaped quotes. Other escapes are kept as they are, as a backslash followed by a character.
+fn chars(body: &str) -> Vec<char> {
+    let mut chars = Vec::new();
+    let mut iter = body.chars();
+    while let Some(c) = iter.next() {
+        match c {
+            '\\' => match iter.next() {
+                Some(quote @ '\'') | Some(quote @ '"') => chars.push(quote),
+                Some(other) => {
+                    chars.push('\\');
+                    chars.push(other);
+                }
+                None => chars.push('\\'),
+            },
+            _ => chars.push(c),
+        }
+    }
+    chars
+}
+
+/// Re-quotes the string literal `raw` (including its quotes) in the given `style`. Like `black`,
+/// the preferred quote is only used if it doesn't need more escaping than the other one.
+pub fn requote(raw: &str, style: QuoteStyle) -> Cow<'_, str> {
+    let (preferred, other) = match style {
+        QuoteStyle::Preserve => return Cow::Borrowed(raw),
+        QuoteStyle::Single => ('\'', '"'),
+        QuoteStyle::Double => ('"', '\''),
+    };
+
+    let chars = chars(&raw[1..raw.len() - 1]);
+    let count = |quote| chars.iter().filter(|&&c| c == quote).count();
+    let quote = if count(preferred) > count(other) {
+        other
+    } else {
+        preferred
+    };
+
+    let mut requoted = String::with_capacity(raw.len());
+    requoted.push(quote);
+    for c in chars {
+        if c == quote {
+            requoted.push('\\');
+        }
+        requoted.push(c);
+    }
+    requoted.push(quote);
+
+    if requoted == raw {
+        Cow::Borrowed(raw)
+    } else {
+        Cow::Owned(requoted)
+    }
+}
+
+/// Splits the body of a string literal into its characters, keeping each escape sequence like
+/// `\n`, `\x1b` or `\N{BULLET}` together as one.
+fn units(body: &str) -> Vec<&str> {
+    let mut units = Vec::new();
+    let mut rest = body;
+    while let Some(c) = rest.chars().next() {
+        let mut len = c.len_utf8();
+        if c == '\\' {
+            let escaped = &rest[1..];
+            len += match escaped.chars().next() {
+                Some('N') if escaped[1..].starts_with('{') => {
+                    escaped.find('}').map_or(escaped.len(), |close| close + 1)
+                }
+                Some(next) => {
+                    let (max_digits, radix) = match next {
+                        'x' => (2, 16),
+                        'u' => (4, 16),
+                        'U' => (8, 16),
+                        '0'..='7' => (2, 8),
+                        _ => (0, 10),
+                    };
+                    next.len_utf8()
+                        + escaped[next.len_utf8()..]
+                            .chars()
+                            .take(max_digits)
+                            .take_while(|c| c.is_digit(radix))
+                            .count()
+                }
+                None => 0,
+            };
+        }
+        units.push(&rest[..len]);
+        rest = &rest[len..];
+    }
+    units
+}
+
+/// A string literal of `len` asterisks in `quote`s, which replaces a redacted string.
+pub(crate) fn mask(quote: char, len: usize) -> String {
+    format!("{}{}{}", quote, "*".repeat(len), quote)
+}
+
+/// The text of the string literal `raw`, with its escape sequences replaced by the characters
+/// they stand for like in Python. Named escapes like `\N{BULLET}` are kept as they are, since
+/// looking up their names needs the Unicode database, as are invalid escapes.
+pub(crate) fn decode(raw: &str) -> String {
+    let mut text = String::with_capacity(raw.len());
+    for unit in units(&raw[1..raw.len() - 1]) {
+        let escaped = match unit.strip_prefix('\\') {
+            Some(escaped) => escaped,
+            None => {
+                text.push_str(unit);
+                continue;
+            }
+        };
+        let decoded = match escaped.chars().next() {
+            // A backslash at the end of a line continues the string on the next one.
+            Some('\n') => continue,
+            Some('a') => Some('\x07'),
+            Some('b') => Some('\x08'),
+            Some('f') => Some('\x0c'),
+            Some('n') => Some('\n'),
+            Some('r') => Some('\r'),
+            Some('t') => Some('\t'),
+            Some('v') => Some('\x0b'),
+            Some(c @ '\\') | Some(c @ '\'') | Some(c @ '"') => Some(c),
+            Some('x') | Some('u') | Some('U') => u32::from_str_radix(&escaped[1..], 16)
+                .ok()
+                .map(|code| char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)),
+            Some('0'..='7') => u32::from_str_radix(escaped, 8)
+                .ok()
+                .and_then(char::from_u32),
+            _ => None,
+        };
+        match decoded {
+            Some(c) => text.push(c),
+            None => text.push_str(unit),
+        }
+    }
+    text
+}
+
+/// The number of characters in the string literal `raw`, counting escape sequences as one.
+pub(crate) fn char_count(raw: &str) -> usize {
+    units(&raw[1..raw.len() - 1]).len()
+}
+
+/// Shortens the string literal `raw` to its first `max_chars` characters, counting escape
+/// sequences as one. Returns the shortened literal and the number of characters removed, or
+/// `None` if `raw` isn't longer than that.
+pub(crate) fn truncate(raw: &str, max_chars: usize) -> Option<(String, usize)> {
+    let (quote, body) = (&raw[..1], &raw[1..raw.len() - 1]);
+    let units = units(body);
+    if units.len() <= max_chars {
+        return None;
+    }
+
+    let kept: String = units[..max_chars].concat();
+    Some((
+        format!("{}{}{}", quote, kept, quote),
+        units.len() - max_chars,
+    ))
+}
+
+/// Splits the string literal `raw` into literals of at most `width` characters each, including
+/// their quotes, which Python concatenates back into the same string. Escape sequences are never
+/// split, so a chunk may be wider than `width` if it's too small to hold one.
+pub(crate) fn split(raw: &str, width: usize) -> Vec<String> {
+    let (quote, body) = (&raw[..1], &raw[1..raw.len() - 1]);
+    let width = width.saturating_sub(2).max(1);
+
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+    let mut chunk_width = 0;
+    for unit in units(body) {
+        let unit_width = unit.chars().count();
+        if chunk_width > 0 && chunk_width + unit_width > width {
+            chunks.push(format!("{}{}{}", quote, chunk, quote));
+            chunk.clear();
+            chunk_width = 0;
+        }
+        chunk.push_str(unit);
+        chunk_width += unit_width;
+    }
+    if chunk_width > 0 || chunks.is_empty() {
+        chunks.push(format!("{}{}{}", quote, chunk, quote));
+    }
+    chunks
+}
@@ -0,0 +1,200 @@
+use pretty::{BoxDoc, Doc};
+
+use super::{Arg, Value};
+use crate::value::highlight::Highlight;
+use crate::value::print::{elide, elided_marker, highlighted, to_one_line, PrintOptions};
+
+/// The number of spaces between the columns of a table.
+const COLUMN_GAP: usize = 2;
+
+/// A field of one row of a table, printed on one line.
+struct Cell {
+    text: String,
+    highlight: Option<Highlight>,
+    is_number: bool,
+}
+
+impl Cell {
+    fn new(value: &Value, options: &PrintOptions, depth: usize) -> Cell {
+        let highlight = match *value {
+            Value::Int(_) => Some(Highlight::Int),
+            Value::Float(_) => Some(Highlight::Float),
+            Value::Bool(_) | Value::Symbol("None") => Some(Highlight::Bool),
+            Value::Symbol(_) => Some(Highlight::Symbol),
+            Value::Str(_) => Some(Highlight::Str),
+            _ => None,
+        };
+        Cell {
+            text: to_one_line(&value.to_doc_at_depth(options, depth)),
+            highlight,
+            is_number: matches!(value, Value::Int(_) | Value::Float(_)),
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn to_doc<'a>(&self) -> Doc<'a, BoxDoc<'a, Highlight>, Highlight> {
+        match self.highlight {
+            Some(highlight) => highlighted(self.text.clone(), highlight),
+            None => Doc::text(self.text.clone()),
+        }
+    }
+}
+
+/// Lays out one line of a table, padding each column to its width in `widths`. Columns of numbers
+/// are aligned to the right.
+fn line_to_doc<'a>(
+    cells: Vec<Doc<'a, BoxDoc<'a, Highlight>, Highlight>>,
+    cell_widths: &[usize],
+    widths: &[usize],
+    right_aligned: &[bool],
+) -> Doc<'a, BoxDoc<'a, Highlight>, Highlight> {
+    let mut line = Doc::nil();
+    let mut gap = 0;
+    for (i, cell) in cells.into_iter().enumerate() {
+        let padding = widths[i] - cell_widths[i];
+        if right_aligned[i] {
+            line = line
+                .append(Doc::text(" ".repeat(gap + padding)))
+                .append(cell);
+            gap = COLUMN_GAP;
+        } else {
+            line = line.append(Doc::text(" ".repeat(gap))).append(cell);
+            gap = COLUMN_GAP + padding;
+        }
+    }
+    line
+}
+
+impl<'value> Value<'value> {
+    /// The name and kwargs shared by the constructors `xs`, or `None` unless all of them are calls
+    /// of the same constructor with the same kwargs, in the same order.
+    fn table_shape(xs: &[Value<'value>]) -> Option<(&'value str, Vec<&'value str>)> {
+        let mut shape: Option<(&str, Vec<&str>)> = None;
+        for x in xs {
+            let (name, args) = match *x {
+                Value::Constructor(name, ref args) if !args.is_empty() => (name, args),
+                _ => return None,
+            };
+            let fields = args
+                .iter()
+                .map(|arg| match *arg {
+                    Arg::Kwarg(key, _) => Some(key),
+                    Arg::Arg(_) => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            match shape {
+                Some((shape_name, ref shape_fields))
+                    if shape_name != name || *shape_fields != fields =>
+                {
+                    return None
+                }
+                Some(_) => {}
+                None => shape = Some((name, fields)),
+            }
+        }
+        shape
+    }
+
+    /// Lays out the items `xs` of a list or tuple as a table with a column for each kwarg, if
+    /// they're at least two calls of the same constructor with the same kwargs. The rows are
+    /// always printed one per line, however wide they are.
+    pub(crate) fn table_to_doc(
+        open: &'static str,
+        xs: &[Value<'value>],
+        close: &'static str,
+        options: &PrintOptions,
+        depth: usize,
+    ) -> Option<Doc<'value, BoxDoc<'value, Highlight>, Highlight>> {
+        if xs.len() < 2 {
+            return None;
+        }
+        let (name, fields) = Self::table_shape(xs)?;
+
+        let (head, elided, tail) = elide(xs, options).unwrap_or((xs, 0, &[]));
+        let rows_to_cells = |rows: &[Value]| -> Vec<Vec<Cell>> {
+            rows.iter()
+                .map(|row| match row {
+                    Value::Constructor(_, args) => args
+                        .iter()
+                        .map(|arg| match arg {
+                            Arg::Kwarg(_, value) | Arg::Arg(value) => {
+                                Cell::new(value, options, depth + 2)
+                            }
+                        })
+                        .collect(),
+                    _ => unreachable!("the rows of a table are constructors"),
+                })
+                .collect()
+        };
+        let (head, tail) = (rows_to_cells(head), rows_to_cells(tail));
+        let rows = || head.iter().chain(&tail);
+
+        let header_widths: Vec<_> = fields.iter().map(|field| field.chars().count()).collect();
+        let widths: Vec<_> = (0..fields.len())
+            .map(|i| {
+                rows()
+                    .map(|row| row[i].width())
+                    .chain(Some(header_widths[i]))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let right_aligned: Vec<_> = (0..fields.len())
+            .map(|i| rows().all(|row| row[i].is_number))
+            .collect();
+        let row_to_doc = |row: &Vec<Cell>| {
+            let cell_widths: Vec<_> = row.iter().map(Cell::width).collect();
+            line_to_doc(
+                row.iter().map(Cell::to_doc).collect(),
+                &cell_widths,
+                &widths,
+                &right_aligned,
+            )
+        };
+
+        let header = line_to_doc(
+            fields
+                .iter()
+                .map(|&field| highlighted(field, Highlight::Kwarg))
+                .collect(),
+            &header_widths,
+            &widths,
+            &right_aligned,
+        );
+        let rule = line_to_doc(
+            widths
+                .iter()
+                .map(|&width| highlighted("-".repeat(width), Highlight::Punctuation))
+                .collect(),
+            &widths,
+            &widths,
+            &right_aligned,
+        );
+        let marker = if elided > 0 {
+            Some(elided_marker(elided))
+        } else {
+            None
+        };
+        let lines = vec![header, rule]
+            .into_iter()
+            .chain(head.iter().map(row_to_doc))
+            .chain(marker)
+            .chain(tail.iter().map(row_to_doc));
+
+        Some(
+            highlighted(open, Highlight::Punctuation)
+                .append(Doc::text("  "))
+                .append(highlighted("# ", Highlight::Punctuation))
+                .append(highlighted(name, Highlight::Constructor))
+                .append(Doc::newline())
+                .append(Doc::intersperse(lines, Doc::newline()))
+                .nest(options.indent)
+                .append(Doc::newline())
+                .append(highlighted(close, Highlight::Punctuation)),
+        )
+    }
+}
@@ -0,0 +1,263 @@
+use std::fmt;
+
+use termion::{color, style};
+
+use crate::value::highlight::Highlight;
+
+/// A terminal color.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    /// One of the 256 colors of the ANSI palette. The first 16 are the basic colors whose exact
+    /// shade depends on the terminal.
+    Ansi(u8),
+    Rgb(u8, u8, u8),
+}
+
+const COLOR_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+impl Color {
+    /// Parses a basic color name like `red` or `bright-red`, an ANSI palette index like `208`,
+    /// or a hex color like `#ff8800`.
+    fn parse(s: &str) -> Option<Color> {
+        if let Some(i) = COLOR_NAMES.iter().position(|&name| name == s) {
+            return Some(Color::Ansi(i as u8));
+        }
+
+        if let Some(name) = s.strip_prefix("bright-") {
+            let i = COLOR_NAMES.iter().position(|&n| n == name)?;
+            return Some(Color::Ansi(i as u8 + 8));
+        }
+
+        if let Some(hex) = s.strip_prefix('#') {
+            if hex.len() != 6 || !hex.is_ascii() {
+                return None;
+            }
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+            return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+        }
+
+        s.parse().ok().map(Color::Ansi)
+    }
+}
+
+/// How a kind of text is displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Style {
+    /// The foreground color, or `None` to use the terminal's default.
+    pub fg: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl Style {
+    const fn fg(color: u8) -> Style {
+        Style {
+            fg: Some(Color::Ansi(color)),
+            bold: false,
+            italic: false,
+            underline: false,
+        }
+    }
+
+    const fn bold(self) -> Style {
+        Style { bold: true, ..self }
+    }
+
+    /// Parses a space-separated list of a color (see `Color::parse`) and any of the words `bold`,
+    /// `italic` and `underline`. The color `default` leaves the text uncolored.
+    fn parse(s: &str) -> Result<Style, String> {
+        let mut style = Style::default();
+        for word in s.split_whitespace() {
+            match word {
+                "bold" => style.bold = true,
+                "italic" => style.italic = true,
+                "underline" => style.underline = true,
+                "default" => style.fg = None,
+                _ => {
+                    style.fg = Some(
+                        Color::parse(word)
+                            .ok_or_else(|| format!("unknown color or style `{}`", word))?,
+                    )
+                }
+            }
+        }
+        Ok(style)
+    }
+
+    /// The ANSI escape codes which switch to this style.
+    pub fn escape_code(&self) -> String {
+        let mut code = String::new();
+        if self.bold {
+            code.push_str(style::Bold.as_ref());
+        }
+        if self.italic {
+            code.push_str(style::Italic.as_ref());
+        }
+        if self.underline {
+            code.push_str(style::Underline.as_ref());
+        }
+        match self.fg {
+            Some(Color::Ansi(i)) => code.push_str(&color::Fg(color::AnsiValue(i)).to_string()),
+            Some(Color::Rgb(r, g, b)) => code.push_str(&color::Fg(color::Rgb(r, g, b)).to_string()),
+            None => {}
+        }
+        code
+    }
+}
+
+/// The styles used for each kind of highlighted text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub constructor: Style,
+    pub kwarg: Style,
+    pub str: Style,
+    pub int: Style,
+    pub float: Style,
+    pub bool: Style,
+    pub symbol: Style,
+    pub punctuation: Style,
+    pub deleted: Style,
+    pub inserted: Style,
+}
+
+/// A problem found in a theme file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeError {
+    /// The line the problem is on, counting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    /// A theme for terminals with a dark background.
+    pub fn dark() -> Theme {
+        Theme {
+            constructor: Style::fg(4).bold(),
+            kwarg: Style::fg(6),
+            str: Style::fg(2),
+            int: Style::fg(5),
+            float: Style::fg(5),
+            bool: Style::fg(3),
+            symbol: Style::default(),
+            punctuation: Style::fg(8),
+            deleted: Style::fg(1).bold(),
+            inserted: Style::fg(2).bold(),
+        }
+    }
+
+    /// A theme for terminals with a light background, which avoids the pale basic colors.
+    pub fn light() -> Theme {
+        Theme {
+            constructor: Style::fg(19).bold(),
+            kwarg: Style::fg(30),
+            str: Style::fg(28),
+            int: Style::fg(90),
+            float: Style::fg(90),
+            bool: Style::fg(130),
+            symbol: Style::default(),
+            punctuation: Style::fg(244),
+            deleted: Style::fg(124).bold(),
+            inserted: Style::fg(28).bold(),
+        }
+    }
+
+    /// Looks up one of the built-in themes, `dark` or `light`.
+    pub fn named(name: &str) -> Option<Theme> {
+        match name {
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            _ => None,
+        }
+    }
+
+    /// Parses a theme file. Each line assigns a style to a kind of text, like
+    /// `constructor = blue bold`. The kinds are `constructor`, `kwarg`, `str`, `int`, `float`,
+    /// `bool`, `symbol`, `bracket` (or `punctuation`), `deleted` and `inserted`. Kinds which
+    /// aren't assigned keep their style from the `dark` theme, or from the built-in theme named
+    /// by a `base = light` line. Lines starting with `#` are comments.
+    pub fn parse(text: &str) -> Result<Theme, ThemeError> {
+        let mut theme = Theme::default();
+
+        for (i, line) in text.lines().enumerate() {
+            let error = |message: String| ThemeError {
+                line: i + 1,
+                message,
+            };
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match line.find('=') {
+                Some(eq) => (line[..eq].trim(), line[eq + 1..].trim()),
+                None => {
+                    return Err(error(format!(
+                        "expected `<kind> = <style>`, found `{}`",
+                        line
+                    )))
+                }
+            };
+
+            if key == "base" {
+                theme = Theme::named(value)
+                    .ok_or_else(|| error(format!("unknown built-in theme `{}`", value)))?;
+                continue;
+            }
+
+            let style = Style::parse(value).map_err(error)?;
+            *theme
+                .style_mut(key)
+                .ok_or_else(|| error(format!("unknown kind of text `{}`", key)))? = style;
+        }
+
+        Ok(theme)
+    }
+
+    fn style_mut(&mut self, key: &str) -> Option<&mut Style> {
+        match key {
+            "constructor" => Some(&mut self.constructor),
+            "kwarg" => Some(&mut self.kwarg),
+            "str" => Some(&mut self.str),
+            "int" => Some(&mut self.int),
+            "float" => Some(&mut self.float),
+            "bool" => Some(&mut self.bool),
+            "symbol" => Some(&mut self.symbol),
+            "bracket" | "punctuation" => Some(&mut self.punctuation),
+            "deleted" => Some(&mut self.deleted),
+            "inserted" => Some(&mut self.inserted),
+            _ => None,
+        }
+    }
+
+    /// The style used for text highlighted as `highlight`.
+    pub fn style(&self, highlight: Highlight) -> &Style {
+        match highlight {
+            Highlight::Constructor => &self.constructor,
+            Highlight::Kwarg => &self.kwarg,
+            Highlight::Str => &self.str,
+            Highlight::Int => &self.int,
+            Highlight::Float => &self.float,
+            Highlight::Bool => &self.bool,
+            Highlight::Symbol => &self.symbol,
+            Highlight::Punctuation => &self.punctuation,
+            Highlight::Deleted => &self.deleted,
+            Highlight::Inserted => &self.inserted,
+        }
+    }
+}
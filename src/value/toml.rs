@@ -0,0 +1,322 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use pretty::{BoxDoc, Doc};
+
+use super::{Arg, Value};
+use crate::value::highlight::Highlight;
+use crate::value::json::{colliding_kwarg, json_string, ARGS_FIELD};
+use crate::value::print::{float_to_string, highlighted, to_one_line, PrintOptions};
+use crate::value::quotes::decode;
+
+/// How values are converted to TOML.
+#[derive(Debug, Clone, Default)]
+pub struct TomlOptions {
+    /// The key of the constructor names of constructors converted to tables, or `None` to leave
+    /// the names out.
+    pub type_field: Option<String>,
+}
+
+/// A value which can't be converted to TOML.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TomlError {
+    /// The dotted keys of the value, or an empty string for the top level value.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for TomlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "`{}`: {}", self.path, self.message)
+        }
+    }
+}
+
+type TomlDoc<'a> = Doc<'a, BoxDoc<'a, Highlight>, Highlight>;
+
+/// A value converted to TOML, before it's laid out.
+enum Toml {
+    /// A string, number or boolean, written as in TOML.
+    Scalar(String, Highlight),
+    Array(Vec<Toml>),
+    Table(Vec<(String, Toml)>),
+}
+
+impl Toml {
+    /// Whether this value is written as its own table (or array of tables) under a `[header]`,
+    /// rather than inline after its key. Empty tables are written inline, as `{}`.
+    fn is_section(&self) -> bool {
+        match self {
+            Toml::Table(entries) => !entries.is_empty(),
+            Toml::Array(items) => {
+                !items.is_empty() && items.iter().all(|item| matches!(item, Toml::Table(_)))
+            }
+            Toml::Scalar(..) => false,
+        }
+    }
+}
+
+/// `key` as a TOML key, which is quoted unless it's a bare key.
+fn toml_key<'a>(key: &str) -> TomlDoc<'a> {
+    let is_bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if is_bare {
+        highlighted(key.to_string(), Highlight::Kwarg)
+    } else {
+        // Basic TOML strings have the same escapes as JSON strings.
+        highlighted(json_string(key), Highlight::Kwarg)
+    }
+}
+
+/// A float as a TOML number. Unlike JSON, TOML has infinities and NaN.
+fn toml_float(x: f64) -> String {
+    if x.is_nan() {
+        "nan".to_string()
+    } else if x.is_infinite() {
+        if x > 0.0 { "inf" } else { "-inf" }.to_string()
+    } else {
+        float_to_string(x)
+    }
+}
+
+impl<'a> Value<'a> {
+    /// The TOML key for this dict key. Strings are decoded, and other values are written as
+    /// `m-o` prints them.
+    fn toml_key(&self) -> String {
+        match *self {
+            Value::Str(ref raw) => decode(raw),
+            _ => to_one_line(&self.to_doc(&PrintOptions::default())),
+        }
+    }
+
+    fn to_toml(
+        &self,
+        options: &PrintOptions,
+        toml: &TomlOptions,
+        path: &mut Vec<String>,
+    ) -> Result<Toml, TomlError> {
+        Ok(match *self {
+            Value::Bool(x) => Toml::Scalar(x.to_string(), Highlight::Bool),
+            Value::Str(ref raw) => Toml::Scalar(json_string(&decode(raw)), Highlight::Str),
+            Value::Int(x) => Toml::Scalar(x.to_string(), Highlight::Int),
+            Value::Float(x) => Toml::Scalar(toml_float(x), Highlight::Float),
+            Value::Symbol("None") => {
+                return Err(TomlError {
+                    path: path.join("."),
+                    message: "TOML has no `None`".to_string(),
+                })
+            }
+            Value::Symbol(symbol) => Toml::Scalar(json_string(symbol), Highlight::Symbol),
+            Value::Tuple(ref xs) | Value::List(ref xs) => {
+                Toml::Array(to_toml_all(xs.iter(), options, toml, path)?)
+            }
+            Value::Set(ref xs) => {
+                let mut xs: Vec<_> = xs.iter().collect();
+                if options.sort_sets {
+                    xs.sort_by(|x, y| x.total_cmp(y));
+                }
+                Toml::Array(to_toml_all(xs.into_iter(), options, toml, path)?)
+            }
+            Value::Dict(ref pairs) => {
+                let mut pairs: Vec<_> = pairs.iter().collect();
+                if options.sort_dicts {
+                    pairs.sort_by(|(k1, _), (k2, _)| k1.total_cmp(k2));
+                }
+                let entries = pairs
+                    .into_iter()
+                    .map(|(key, value)| to_toml_entry(key.toml_key(), value, options, toml, path))
+                    .collect::<Result<_, _>>()?;
+                toml_table(entries, path)?
+            }
+            Value::Constructor(name, ref args) => {
+                if let Some(message) = colliding_kwarg(name, args, toml.type_field.as_deref()) {
+                    return Err(TomlError {
+                        path: path.join("."),
+                        message,
+                    });
+                }
+                let mut entries = Vec::new();
+                if let Some(ref type_field) = toml.type_field {
+                    entries.push((
+                        type_field.clone(),
+                        Toml::Scalar(json_string(name), Highlight::Constructor),
+                    ));
+                }
+                let positional: Vec<_> = args
+                    .iter()
+                    .filter_map(|arg| match *arg {
+                        Arg::Arg(ref value) => Some(value),
+                        Arg::Kwarg(..) => None,
+                    })
+                    .collect();
+                if !positional.is_empty() {
+                    path.push(ARGS_FIELD.to_string());
+                    let array = to_toml_all(positional.into_iter(), options, toml, path)?;
+                    path.pop();
+                    entries.push((ARGS_FIELD.to_string(), Toml::Array(array)));
+                }
+                for arg in args {
+                    if let Arg::Kwarg(key, ref value) = *arg {
+                        let entry = to_toml_entry(key.to_string(), value, options, toml, path);
+                        entries.push(entry.map_err(|mut error| {
+                            // Unlike other `None`s, kwargs set to it can be left out.
+                            if *value == Value::Symbol("None") {
+                                error.message.push_str(
+                                    ", but kwargs set to it can be left out with `--hide none`",
+                                );
+                            }
+                            error
+                        })?);
+                    }
+                }
+                toml_table(entries, path)?
+            }
+        })
+    }
+
+    /// Converts this value to a TOML document, if it's a constructor or dict. Constructors and
+    /// dicts in it become tables, with the positional args of constructors in an array under
+    /// `__args__`, and their names under `toml.type_field` if it's given. Lists of them become
+    /// arrays of tables. Other symbols than `None`, which TOML has no equivalent of, become
+    /// strings.
+    pub fn to_toml_doc(
+        &self,
+        options: &PrintOptions,
+        toml: &TomlOptions,
+    ) -> Result<TomlDoc<'a>, TomlError> {
+        let entries = match self.to_toml(options, toml, &mut Vec::new())? {
+            Toml::Table(entries) => entries,
+            _ => {
+                return Err(TomlError {
+                    path: String::new(),
+                    message: "only constructors and dicts can be converted to TOML documents"
+                        .to_string(),
+                })
+            }
+        };
+        let mut lines = Vec::new();
+        push_table(&mut lines, None, &entries, &mut Vec::new(), options);
+        Ok(Doc::intersperse(lines, Doc::newline()))
+    }
+}
+
+fn to_toml_all<'tmp, 'a: 'tmp>(
+    xs: impl Iterator<Item = &'tmp Value<'a>>,
+    options: &PrintOptions,
+    toml: &TomlOptions,
+    path: &mut Vec<String>,
+) -> Result<Vec<Toml>, TomlError> {
+    xs.map(|x| x.to_toml(options, toml, path)).collect()
+}
+
+/// A table of the `entries` at `path`, whose keys have to be unique. Dict keys which aren't
+/// strings can become the same as other keys when they're converted to strings, like `1` and
+/// `'1'`.
+fn toml_table(entries: Vec<(String, Toml)>, path: &[String]) -> Result<Toml, TomlError> {
+    let mut keys = HashSet::new();
+    for (key, _) in &entries {
+        if !keys.insert(key) {
+            return Err(TomlError {
+                path: path.join("."),
+                message: format!("the key `{}` appears more than once in the table", key),
+            });
+        }
+    }
+    Ok(Toml::Table(entries))
+}
+
+fn to_toml_entry(
+    key: String,
+    value: &Value,
+    options: &PrintOptions,
+    toml: &TomlOptions,
+    path: &mut Vec<String>,
+) -> Result<(String, Toml), TomlError> {
+    path.push(key);
+    let value = value.to_toml(options, toml, path);
+    let key = path.pop().unwrap_or_default();
+    Ok((key, value?))
+}
+
+/// Lays out `value` after its key, on the same line.
+fn inline_to_doc<'a>(value: &Toml, options: &PrintOptions) -> TomlDoc<'a> {
+    match value {
+        Toml::Scalar(text, highlight) => highlighted(text.clone(), *highlight),
+        Toml::Array(items) => Value::seq_to_doc(
+            "[",
+            items.iter().map(|item| inline_to_doc(item, options)),
+            "]",
+            false,
+            options,
+        ),
+        Toml::Table(entries) if entries.is_empty() => highlighted("{}", Highlight::Punctuation),
+        // Inline tables can't be broken over several lines.
+        Toml::Table(entries) => highlighted("{ ", Highlight::Punctuation)
+            .append(Doc::intersperse(
+                entries
+                    .iter()
+                    .map(|(key, value)| key_value_to_doc(key, value, options)),
+                highlighted(",", Highlight::Punctuation).append(Doc::text(" ")),
+            ))
+            .append(highlighted(" }", Highlight::Punctuation)),
+    }
+}
+
+fn key_value_to_doc<'a>(key: &str, value: &Toml, options: &PrintOptions) -> TomlDoc<'a> {
+    toml_key(key)
+        .append(highlighted(" = ", Highlight::Punctuation))
+        .append(inline_to_doc(value, options))
+}
+
+/// Adds the lines of the table at `path`, starting with its `header` (`[` or `[[`) unless it's
+/// the top level table, followed by the tables in it.
+fn push_table<'a>(
+    lines: &mut Vec<TomlDoc<'a>>,
+    header: Option<(&'static str, &'static str)>,
+    entries: &[(String, Toml)],
+    path: &mut Vec<String>,
+    options: &PrintOptions,
+) {
+    if let Some((open, close)) = header {
+        if !lines.is_empty() {
+            lines.push(Doc::nil());
+        }
+        lines.push(
+            highlighted(open, Highlight::Punctuation)
+                .append(Doc::intersperse(
+                    path.iter().map(|key| toml_key(key)),
+                    highlighted(".", Highlight::Punctuation),
+                ))
+                .append(highlighted(close, Highlight::Punctuation)),
+        );
+    }
+
+    // The keys of a table have to come before the tables in it.
+    for (key, value) in entries {
+        if !value.is_section() {
+            lines.push(key_value_to_doc(key, value, options));
+        }
+    }
+    for (key, value) in entries {
+        path.push(key.clone());
+        match value {
+            Toml::Table(entries) if value.is_section() => {
+                push_table(lines, Some(("[", "]")), entries, path, options)
+            }
+            Toml::Array(items) if value.is_section() => {
+                for item in items {
+                    if let Toml::Table(entries) = item {
+                        push_table(lines, Some(("[[", "]]")), entries, path, options);
+                    }
+                }
+            }
+            _ => {}
+        }
+        path.pop();
+    }
+}
@@ -0,0 +1,180 @@
+use pretty::{BoxDoc, Doc};
+
+use super::{Arg, Value};
+use crate::value::highlight::Highlight;
+use crate::value::print::{elide, elided_marker, highlighted, PrintOptions};
+
+/// The characters which draw the branches of a tree.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TreeGuides {
+    /// Box-drawing characters, like `├── `.
+    Unicode,
+    /// Plain ASCII, like `|-- `, for terminals and fonts without box-drawing characters.
+    Ascii,
+}
+
+impl TreeGuides {
+    /// The start of the line of a child, which is `last` if it has no siblings below it.
+    fn branch(self, last: bool) -> &'static str {
+        match (self, last) {
+            (TreeGuides::Unicode, false) => "├── ",
+            (TreeGuides::Unicode, true) => "└── ",
+            (TreeGuides::Ascii, false) => "|-- ",
+            (TreeGuides::Ascii, true) => "`-- ",
+        }
+    }
+
+    /// The start of the lines of a child's descendants.
+    fn indent(self, last: bool) -> &'static str {
+        match (self, last) {
+            (TreeGuides::Unicode, false) => "│   ",
+            (TreeGuides::Ascii, false) => "|   ",
+            (_, true) => "    ",
+        }
+    }
+}
+
+type Line<'a> = Doc<'a, BoxDoc<'a, Highlight>, Highlight>;
+
+/// A child in a tree: a value along with its kwarg or dict key, or a marker for elided children.
+enum Child<'tmp, 'value> {
+    Value(Option<Line<'value>>, &'tmp Value<'value>),
+    Elided(usize),
+}
+
+impl<'value> Value<'value> {
+    /// The children of this value in a tree, or `None` if it's drawn as a leaf.
+    fn tree_children<'tmp>(
+        &'tmp self,
+        options: &PrintOptions,
+        depth: usize,
+    ) -> Option<Vec<Child<'tmp, 'value>>> {
+        if options
+            .max_depth
+            .is_some_and(|max_depth| depth >= max_depth)
+        {
+            return None;
+        }
+
+        let children: Vec<(Option<Line<'value>>, &Value<'value>)> = match *self {
+            Value::List(ref xs) | Value::Tuple(ref xs) => xs.iter().map(|x| (None, x)).collect(),
+            Value::Set(ref xs) => {
+                let mut xs: Vec<_> = xs.iter().collect();
+                if options.sort_sets {
+                    xs.sort_by(|x, y| x.total_cmp(y));
+                }
+                xs.into_iter().map(|x| (None, x)).collect()
+            }
+            Value::Dict(ref pairs) => {
+                let mut pairs: Vec<_> = pairs.iter().collect();
+                if options.sort_dicts {
+                    pairs.sort_by(|(k1, _), (k2, _)| k1.total_cmp(k2));
+                }
+                pairs
+                    .into_iter()
+                    .map(|(key, value)| (Some(key.to_doc_at_depth(options, depth + 1)), value))
+                    .collect()
+            }
+            Value::Constructor(_, ref args) => args
+                .iter()
+                .map(|arg| match *arg {
+                    Arg::Arg(ref value) => (None, value),
+                    Arg::Kwarg(key, ref value) => (Some(highlighted(key, Highlight::Kwarg)), value),
+                })
+                .collect(),
+            _ => return None,
+        };
+        if children.is_empty() {
+            return None;
+        }
+
+        let to_child = |(key, value): &(Option<Line<'value>>, &'tmp Value<'value>)| {
+            Child::Value(key.clone(), value)
+        };
+        Some(match elide(&children, options) {
+            Some((head, elided, tail)) => head
+                .iter()
+                .map(to_child)
+                .chain(Some(Child::Elided(elided)))
+                .chain(tail.iter().map(to_child))
+                .collect(),
+            None => children.iter().map(to_child).collect(),
+        })
+    }
+
+    /// Lays out this value as an outline like the `tree` command's, with each item of a
+    /// collection or constructor on its own line below it, drawing the branches with `guides`.
+    pub fn to_tree_doc(
+        &self,
+        options: &PrintOptions,
+        guides: TreeGuides,
+    ) -> Doc<'value, BoxDoc<'value, Highlight>, Highlight> {
+        let mut tree = Tree {
+            options,
+            guides,
+            lines: Vec::new(),
+        };
+        tree.push(self, None, "", "", 0);
+        Doc::intersperse(tree.lines, Doc::newline())
+    }
+}
+
+/// The lines of a tree being laid out.
+struct Tree<'o, 'value> {
+    options: &'o PrintOptions,
+    guides: TreeGuides,
+    lines: Vec<Line<'value>>,
+}
+
+impl<'o, 'value> Tree<'o, 'value> {
+    /// Adds the lines of the subtree of `value`. The first starts with `branch` and `key`, and the
+    /// lines of its descendants with `indent`.
+    fn push(
+        &mut self,
+        value: &Value<'value>,
+        key: Option<Line<'value>>,
+        branch: &str,
+        indent: &str,
+        depth: usize,
+    ) {
+        let children = value.tree_children(self.options, depth);
+
+        let label = match (children.is_some(), value) {
+            (true, Value::Constructor(name, _)) => highlighted(*name, Highlight::Constructor),
+            (true, Value::List(_)) => Doc::text("list"),
+            (true, Value::Tuple(_)) => Doc::text("tuple"),
+            (true, Value::Set(_)) => Doc::text("set"),
+            (true, Value::Dict(_)) => Doc::text("dict"),
+            _ => value.to_doc_at_depth(self.options, depth),
+        };
+        let key = match key {
+            Some(key) => key
+                .append(highlighted(":", Highlight::Punctuation))
+                .append(Doc::text(" ")),
+            None => Doc::nil(),
+        };
+        self.lines.push(
+            highlighted(branch.to_string(), Highlight::Punctuation)
+                .append(key)
+                .append(label),
+        );
+
+        let children = match children {
+            Some(children) => children,
+            None => return,
+        };
+        let last = children.len() - 1;
+        for (i, child) in children.into_iter().enumerate() {
+            let branch = format!("{}{}", indent, self.guides.branch(i == last));
+            match child {
+                Child::Value(key, value) => {
+                    let indent = format!("{}{}", indent, self.guides.indent(i == last));
+                    self.push(value, key, &branch, &indent, depth + 1)
+                }
+                Child::Elided(elided) => self.lines.push(
+                    highlighted(branch, Highlight::Punctuation).append(elided_marker(elided)),
+                ),
+            }
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use std::fmt;
+
+use pretty::{BoxDoc, Doc};
+
+use super::{Arg, Value};
+use crate::value::highlight::Highlight;
+use crate::value::json::{colliding_kwarg, json_string, ARGS_FIELD};
+use crate::value::print::{float_to_string, highlighted, to_one_line, PrintOptions};
+use crate::value::quotes::decode;
+
+/// How values are converted to YAML.
+#[derive(Debug, Clone, Default)]
+pub struct YamlOptions {
+    /// The key of the constructor names of constructors converted to mappings, or `None` to tag
+    /// them with their names instead, like `!Dog`.
+    pub type_field: Option<String>,
+}
+
+/// A value which can't be converted to YAML.
+#[derive(Debug, Clone, PartialEq)]
+pub struct YamlError {
+    pub message: String,
+}
+
+impl fmt::Display for YamlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+type YamlDoc<'a> = Doc<'a, BoxDoc<'a, Highlight>, Highlight>;
+
+/// Plain scalars which YAML reads as something other than a string, in lowercase.
+const RESERVED_WORDS: &[&str] = &[
+    "~", "null", "true", "false", "yes", "no", "on", "off", "y", "n", "<<",
+];
+
+/// Whether `text` can be written as a plain scalar, without quotes, and be read back as the same
+/// string. This is stricter than YAML needs: anything that might be read as a number, a date or
+/// a YAML 1.1 boolean like `yes` is quoted.
+fn is_plain(text: &str) -> bool {
+    let first = match text.chars().next() {
+        Some(first) => first,
+        None => return false,
+    };
+    let needs_quotes = "-?:,[]{}#&*!|>'\"%@`.+ ".contains(first)
+        || first.is_ascii_digit()
+        || text.ends_with([' ', ':'])
+        || text.contains(": ")
+        || text.contains(" #")
+        || text
+            .chars()
+            .any(|c| c.is_control() || matches!(c, '\u{2028}' | '\u{2029}' | '\u{feff}'))
+        || RESERVED_WORDS.contains(&text.to_lowercase().as_str());
+    !needs_quotes
+}
+
+/// `text` as a YAML scalar, which is double-quoted unless it can be plain. Double-quoted YAML
+/// strings have the same escapes as JSON strings.
+fn yaml_string(text: &str) -> String {
+    if is_plain(text) {
+        text.to_string()
+    } else {
+        json_string(text)
+    }
+}
+
+/// A float as a YAML scalar, with the exponent sign that YAML 1.1 needs to read numbers like
+/// `1.0e+100` as floats.
+fn yaml_float(x: f64) -> String {
+    if x.is_nan() {
+        return ".nan".to_string();
+    } else if x.is_infinite() {
+        return if x > 0.0 { ".inf" } else { "-.inf" }.to_string();
+    }
+
+    let number = float_to_string(x);
+    match number.split_once('e') {
+        Some((mantissa, exponent)) if !exponent.starts_with('-') => {
+            format!("{}e+{}", mantissa, exponent)
+        }
+        _ => number,
+    }
+}
+
+/// A converted value, which is laid out differently depending on whether it can follow its key
+/// or `-` on the same line.
+enum Node<'a> {
+    /// A scalar or an empty collection.
+    Inline(YamlDoc<'a>),
+    /// A block mapping or sequence, with its tag if it's a constructor.
+    Block {
+        tag: Option<&'a str>,
+        lines: YamlDoc<'a>,
+    },
+}
+
+/// The tag of a constructor called `name`.
+fn tag_doc<'a>(name: &'a str) -> YamlDoc<'a> {
+    highlighted(format!("!{}", name), Highlight::Constructor)
+}
+
+impl<'a> Node<'a> {
+    /// Lays out this node as the value of a mapping entry, after its `key:`.
+    fn after_key(self, options: &PrintOptions) -> YamlDoc<'a> {
+        match self {
+            Node::Inline(doc) => Doc::text(" ").append(doc),
+            Node::Block { tag, lines } => {
+                let tag = match tag {
+                    Some(name) => Doc::text(" ").append(tag_doc(name)),
+                    None => Doc::nil(),
+                };
+                tag.append(Doc::newline().append(lines).nest(options.indent))
+            }
+        }
+    }
+
+    /// Lays out this node as an item of a sequence, after its `-`. Untagged blocks start on the
+    /// same line, like in `- name: Pip`.
+    fn after_dash(self) -> YamlDoc<'a> {
+        Doc::text(" ").append(self.into_doc()).nest(2)
+    }
+
+    fn into_doc(self) -> YamlDoc<'a> {
+        match self {
+            Node::Inline(doc) => doc,
+            Node::Block {
+                tag: Some(name),
+                lines,
+            } => tag_doc(name).append(Doc::newline()).append(lines),
+            Node::Block { tag: None, lines } => lines,
+        }
+    }
+}
+
+impl<'a> Value<'a> {
+    /// The YAML mapping key for this dict key. Scalars are written as YAML scalars, and other
+    /// values as strings of how `m-o` prints them.
+    fn yaml_key(
+        &self,
+        options: &PrintOptions,
+        yaml: &YamlOptions,
+    ) -> Result<YamlDoc<'a>, YamlError> {
+        Ok(match *self {
+            Value::Tuple(_)
+            | Value::List(_)
+            | Value::Set(_)
+            | Value::Dict(_)
+            | Value::Constructor(..) => highlighted(
+                yaml_string(&to_one_line(&self.to_doc(&PrintOptions::default()))),
+                Highlight::Kwarg,
+            ),
+            _ => self.yaml_node(options, yaml)?.into_doc(),
+        })
+    }
+
+    fn yaml_node(&self, options: &PrintOptions, yaml: &YamlOptions) -> Result<Node<'a>, YamlError> {
+        Ok(match *self {
+            Value::Bool(x) => Node::Inline(highlighted(x.to_string(), Highlight::Bool)),
+            Value::Str(ref raw) => {
+                Node::Inline(highlighted(yaml_string(&decode(raw)), Highlight::Str))
+            }
+            Value::Int(x) => Node::Inline(highlighted(x.to_string(), Highlight::Int)),
+            Value::Float(x) => Node::Inline(highlighted(yaml_float(x), Highlight::Float)),
+            Value::Symbol("None") => Node::Inline(highlighted("null", Highlight::Bool)),
+            Value::Symbol(symbol) => {
+                Node::Inline(highlighted(yaml_string(symbol), Highlight::Symbol))
+            }
+            Value::Tuple(ref xs) | Value::List(ref xs) => {
+                yaml_sequence(None, xs.iter(), options, yaml)?
+            }
+            Value::Set(ref xs) => {
+                let mut xs: Vec<_> = xs.iter().collect();
+                if options.sort_sets {
+                    xs.sort_by(|x, y| x.total_cmp(y));
+                }
+                yaml_sequence(None, xs.into_iter(), options, yaml)?
+            }
+            Value::Dict(ref pairs) => {
+                let mut pairs: Vec<_> = pairs.iter().collect();
+                if options.sort_dicts {
+                    pairs.sort_by(|(k1, _), (k2, _)| k1.total_cmp(k2));
+                }
+                let entries = pairs
+                    .into_iter()
+                    .map(|(key, value)| {
+                        Ok((
+                            key.yaml_key(options, yaml)?,
+                            value.yaml_node(options, yaml)?,
+                        ))
+                    })
+                    .collect::<Result<_, _>>()?;
+                yaml_mapping(None, entries, options)
+            }
+            Value::Constructor(name, ref args) => {
+                if let Some(message) = colliding_kwarg(name, args, yaml.type_field.as_deref()) {
+                    return Err(YamlError { message });
+                }
+                let positional: Vec<_> = args
+                    .iter()
+                    .filter_map(|arg| match *arg {
+                        Arg::Arg(ref value) => Some(value),
+                        Arg::Kwarg(..) => None,
+                    })
+                    .collect();
+                let has_kwargs = positional.len() < args.len();
+                let key = |key: &str| highlighted(yaml_string(key), Highlight::Kwarg);
+
+                let (tag, mut entries) = match yaml.type_field {
+                    Some(ref type_field) => (
+                        None,
+                        vec![(
+                            key(type_field),
+                            Node::Inline(highlighted(yaml_string(name), Highlight::Constructor)),
+                        )],
+                    ),
+                    // Constructors with only positional args are tagged sequences, like
+                    // `!datetime.date [2020, 1, 1]`.
+                    None if !positional.is_empty() && !has_kwargs => {
+                        return yaml_sequence(Some(name), positional.into_iter(), options, yaml)
+                    }
+                    None => (Some(name), Vec::new()),
+                };
+                if !positional.is_empty() {
+                    entries.push((
+                        key(ARGS_FIELD),
+                        yaml_sequence(None, positional.into_iter(), options, yaml)?,
+                    ));
+                }
+                for arg in args {
+                    if let Arg::Kwarg(name, ref value) = *arg {
+                        entries.push((key(name), value.yaml_node(options, yaml)?));
+                    }
+                }
+                yaml_mapping(tag, entries, options)
+            }
+        })
+    }
+
+    /// Converts this value to YAML. Dicts become mappings and tuples, lists and sets become
+    /// sequences. Constructors become mappings tagged with their names, like `!Dog`, with their
+    /// positional args under `__args__`, or just tagged sequences if they only have positional
+    /// args. If `yaml.type_field` is given, constructors are untagged mappings with their names
+    /// under that key instead. `None` becomes `null` and other symbols become strings.
+    /// Constructors with kwargs called like the keys of their names or positional args can't be
+    /// converted.
+    pub fn to_yaml_doc(
+        &self,
+        options: &PrintOptions,
+        yaml: &YamlOptions,
+    ) -> Result<YamlDoc<'a>, YamlError> {
+        Ok(self.yaml_node(options, yaml)?.into_doc())
+    }
+}
+
+/// A sequence of the items `xs`, tagged with `tag` if it's a constructor.
+fn yaml_sequence<'tmp, 'a: 'tmp>(
+    tag: Option<&'a str>,
+    xs: impl Iterator<Item = &'tmp Value<'a>>,
+    options: &PrintOptions,
+    yaml: &YamlOptions,
+) -> Result<Node<'a>, YamlError> {
+    let items = xs
+        .map(|x| {
+            Ok(highlighted("-", Highlight::Punctuation)
+                .append(x.yaml_node(options, yaml)?.after_dash()))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(yaml_block(tag, items, "[]"))
+}
+
+/// A mapping of the `entries`, tagged with `tag` if it's a constructor.
+fn yaml_mapping<'a>(
+    tag: Option<&'a str>,
+    entries: Vec<(YamlDoc<'a>, Node<'a>)>,
+    options: &PrintOptions,
+) -> Node<'a> {
+    let entries: Vec<_> = entries
+        .into_iter()
+        .map(|(key, value)| {
+            key.append(highlighted(":", Highlight::Punctuation))
+                .append(value.after_key(options))
+        })
+        .collect();
+    yaml_block(tag, entries, "{}")
+}
+
+/// A block of `lines`, or the flow collection `empty` (with the tag, if any) if there are none.
+fn yaml_block<'a>(tag: Option<&'a str>, lines: Vec<YamlDoc<'a>>, empty: &'static str) -> Node<'a> {
+    if !lines.is_empty() {
+        return Node::Block {
+            tag,
+            lines: Doc::intersperse(lines, Doc::newline()),
+        };
+    }
+
+    let empty = highlighted(empty, Highlight::Punctuation);
+    Node::Inline(match tag {
+        Some(name) => tag_doc(name).append(Doc::text(" ")).append(empty),
+        None => empty,
+    })
+}
@@ -0,0 +1,82 @@
+use std::convert::TryFrom;
+
+use m_o::value::ast::{Kind, Node};
+use m_o::value::Value;
+
+#[test]
+fn test_dump_ast() {
+    let source = include_str!("simple_tree.txt");
+    let value = Value::try_from(source.trim()).unwrap();
+    assert_eq!(
+        Node::new(source, &value).to_string(),
+        r#"Constructor 0..52 Dog
+  Kwarg 4..14 name
+    Str 9..14 "Pip"
+  Kwarg 16..21 age
+    Int 20..21 7
+  Kwarg 23..51 friends
+    List 31..51
+      Str 32..40 "Quincy"
+      Str 42..50 "Digger""#
+    );
+}
+
+#[test]
+fn test_ast_spans() {
+    let source = include_str!("ast_test.txt");
+    let value = Value::try_from(source.trim()).unwrap();
+    let ast = Node::new(source, &value);
+
+    assert_eq!(ast.kind, Kind::Constructor);
+    assert_eq!(ast.span, 0..source.trim().len());
+
+    // `Call(fn=Ident(name='exit'), ...)`
+    let call = &ast.children[0].children[0].children[0];
+    assert_eq!(call.kind, Kind::Constructor);
+    assert_eq!(call.name, Some("Call"));
+    let ident = &call.children[0];
+    assert_eq!(
+        (ident.kind, ident.text),
+        (Kind::Kwarg, "fn=Ident(name='exit')")
+    );
+    assert_eq!(ident.children[0].text, "Ident(name='exit')");
+}
+
+#[test]
+fn test_ast_whitespace_and_trailing_commas() {
+    let source = "  {(3,): Foo.Bar, 'k': [\n    1.5,\n    True,\n]}\n";
+    let value = Value::try_from(source.trim()).unwrap();
+    assert_eq!(
+        Node::new(source, &value).to_string(),
+        "Dict 2..46
+  Entry 3..16
+    Tuple 3..7
+      Int 4..5 3
+    Symbol 9..16 Foo.Bar
+  Entry 18..45
+    Str 18..21 'k'
+    List 23..45
+      Float 29..32 1.5
+      Bool 38..42 True"
+    );
+}
+
+#[test]
+fn test_ast_aligned_source() {
+    let source = "Dog(\n    age =7,\n    toys={'ball' : 1, 'rope': 2.5},\n)";
+    let value = Value::try_from(source).unwrap();
+    assert_eq!(
+        Node::new(source, &value).to_string(),
+        "Constructor 0..54 Dog
+  Kwarg 9..15 age
+    Int 14..15 7
+  Kwarg 21..51 toys
+    Dict 26..51
+      Entry 27..37
+        Str 27..33 'ball'
+        Int 36..37 1
+      Entry 39..50
+        Str 39..45 'rope'
+        Float 47..50 2.5"
+    );
+}
@@ -0,0 +1,86 @@
+use std::convert::TryFrom;
+
+use m_o::value::diff::{diff, Diff, Entry, Key};
+use m_o::value::print::PrintOptions;
+use m_o::value::Value;
+
+fn diff_to_string(old: &str, new: &str, columns: usize) -> String {
+    let old = Value::try_from(old).expect("old value parses");
+    let new = Value::try_from(new).expect("new value parses");
+    let options = PrintOptions {
+        columns,
+        ..Default::default()
+    };
+    diff(&old, &new)
+        .to_doc(&options)
+        .pretty(columns)
+        .to_string()
+}
+
+#[test]
+fn test_equal_values_are_same() {
+    let value = Value::try_from("Dog(name='Pip', age=7)").unwrap();
+    assert!(diff(&value, &value.clone()).is_same());
+}
+
+#[test]
+fn test_reordered_values_are_same() {
+    for (old, new) in &[
+        ("{'a': 1, 'b': 2}", "{'b': 2, 'a': 1}"),
+        ("{1, 2}", "{2, 1}"),
+        ("Dog(a=1, b=2)", "Dog(b=2, a=1)"),
+        ("[Dog(a=1, b=2)]", "[Dog(b=2, a=1)]"),
+    ] {
+        let old = Value::try_from(*old).unwrap();
+        let new = Value::try_from(*new).unwrap();
+        assert!(diff(&old, &new).is_same());
+    }
+}
+
+#[test]
+fn test_kwargs_are_matched_by_name() {
+    let old = Value::try_from("Dog(name='Pip', age=7)").unwrap();
+    let new = Value::try_from("Dog(age=8, name='Pip')").unwrap();
+
+    assert_eq!(
+        diff(&old, &new),
+        Diff::Collection(
+            &old,
+            vec![
+                Entry::Kept(
+                    Some(Key::Kwarg("name")),
+                    Diff::Same(&Value::Str("'Pip'".into()))
+                ),
+                Entry::Kept(
+                    Some(Key::Kwarg("age")),
+                    Diff::Replaced(&Value::Int(7), &Value::Int(8))
+                ),
+            ]
+        )
+    );
+}
+
+#[test]
+fn test_diff_to_string() {
+    assert_eq!(
+        diff_to_string(
+            "Dog(name='Pip', friends=['Quincy', 'Digger'], tags={'a': 1, 'b': 2})",
+            "Dog(name='Pep', friends=['Quincy', 'Rex', 'Digger'], tags={'c': 3, 'a': 1}, age=7)",
+            1000,
+        ),
+        "Dog(name=[-'Pip'-] {+'Pep'+}, friends=['Quincy', {+'Rex'+}, 'Digger'], \
+         tags={'a': 1, [-'b': 2-], {+'c': 3+}}, {+age=7+})"
+    );
+}
+
+#[test]
+fn test_replaced_collection_to_string() {
+    let expected = r#"Dog(
+    friends=[-['Quincy']-] {+{'Quincy'}+}
+)"#;
+
+    assert_eq!(
+        diff_to_string("Dog(friends=['Quincy'])", "Dog(friends={'Quincy'})", 41),
+        expected
+    );
+}
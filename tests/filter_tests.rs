@@ -0,0 +1,156 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+
+use m_o::value::filter::{default_redacted_fields, FieldPattern, Filter, HiddenValue};
+use m_o::value::print::PrintOptions;
+use m_o::value::Value;
+
+fn filtered_to_string(input: &str, filter: &Filter) -> String {
+    let options = PrintOptions {
+        columns: 1000,
+        ..Default::default()
+    };
+    let value = Value::try_from(input).unwrap();
+    filter
+        .apply(&value)
+        .to_doc(&options)
+        .pretty(options.columns)
+        .to_string()
+}
+
+const USER: &str = "User(id=1, name='Ann', email=None, admin=False, tags=[], nick='', \
+                    pet=Dog(name='Pip', owner=None), count=0)";
+
+#[test]
+fn test_hide_empty() {
+    let filter = Filter {
+        hide: HiddenValue::ALL.to_vec(),
+        ..Default::default()
+    };
+    assert_eq!(
+        filtered_to_string(USER, &filter),
+        "User(id=1, name='Ann', pet=Dog(name='Pip', ...), count=0, ...)"
+    );
+
+    // Constructors which keep all their kwargs are left as they are, as are positional args and
+    // the items of collections.
+    assert_eq!(
+        filtered_to_string(
+            "[None, Point(0, None), {'a': []}, Dog(name='Pip')]",
+            &filter
+        ),
+        "[None, Point(0, None), {'a': []}, Dog(name='Pip')]"
+    );
+
+    let filter = Filter {
+        hide_silently: true,
+        ..filter
+    };
+    assert_eq!(
+        filtered_to_string(USER, &filter),
+        "User(id=1, name='Ann', pet=Dog(name='Pip'), count=0)"
+    );
+}
+
+#[test]
+fn test_hide_some_kinds() {
+    let filter = Filter {
+        hide: vec![HiddenValue::None, HiddenValue::Empty],
+        ..Default::default()
+    };
+    assert_eq!(
+        filtered_to_string(USER, &filter),
+        "User(id=1, name='Ann', admin=False, pet=Dog(name='Pip', ...), count=0, ...)"
+    );
+
+    assert_eq!(
+        filtered_to_string(USER, &Filter::default()),
+        "User(id=1, name='Ann', email=None, admin=False, tags=[], nick='', \
+         pet=Dog(name='Pip', owner=None), count=0)"
+    );
+}
+
+const JOB: &str = "Job(id=7, status='failed', _sa_instance_state=State(), \
+                   steps=[Step(id=1, status='ok', error=None), Step(id=2, error=Error(code=3))], \
+                   meta={'error': 'x', 'owner': User(id=3, name='Ann')})";
+
+fn patterns(patterns: &[&str]) -> Vec<FieldPattern> {
+    patterns
+        .iter()
+        .map(|pattern| pattern.parse().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_only() {
+    let filter = Filter {
+        only: patterns(&["id", "*.error"]),
+        ..Default::default()
+    };
+    assert_eq!(
+        filtered_to_string(JOB, &filter),
+        "Job(id=7, steps=[Step(id=1, error=None, ...), Step(id=2, error=Error(code=3))], \
+         meta={'error': 'x', 'owner': User(id=3, ...)}, ...)"
+    );
+
+    let filter = Filter {
+        only: patterns(&["m?ta.own*"]),
+        ..Default::default()
+    };
+    assert_eq!(
+        filtered_to_string(JOB, &filter),
+        "Job(meta={'owner': User(id=3, name='Ann')}, ...)"
+    );
+}
+
+#[test]
+fn test_exclude() {
+    let filter = Filter {
+        exclude: patterns(&["_sa_instance_state", "steps.*", "owner.name"]),
+        ..Default::default()
+    };
+    assert_eq!(
+        filtered_to_string(JOB, &filter),
+        "Job(id=7, status='failed', steps=[Step(...), Step(...)], \
+         meta={'error': 'x', 'owner': User(id=3, ...)}, ...)"
+    );
+
+    assert!("status..id".parse::<FieldPattern>().is_err());
+}
+
+#[test]
+fn test_redact() {
+    let config = "Config(user='ann', password='hunter2', api_key=None, \
+                  db=Db(db_password=\"p\\n\\x1bq\", pin=1234), \
+                  headers={'Authorization': 'Bearer abc'}, access_token=Token(value='xyz'))";
+
+    let filter = Filter {
+        redact: default_redacted_fields(),
+        ..Default::default()
+    };
+    assert_eq!(
+        filtered_to_string(config, &filter),
+        "Config(user='ann', password='*******', api_key=None, \
+         db=Db(db_password=\"****\", pin=1234), headers={'Authorization': '**********'}, \
+         access_token=Token(***))"
+    );
+    // Masks are new string literals, rather than parts of the input.
+    assert_eq!(
+        filter.apply(&Value::try_from("{'token': 'abc'}").unwrap()),
+        Value::Dict(vec![(
+            Value::Str("'token'".into()),
+            Value::Str(Cow::Owned("'***'".to_string()))
+        )])
+    );
+
+    let filter = Filter {
+        redact: patterns(&["DB.PIN", "user"]),
+        ..Default::default()
+    };
+    assert_eq!(
+        filtered_to_string(config, &filter),
+        "Config(user='***', password='hunter2', api_key=None, \
+         db=Db(db_password=\"p\\n\\x1bq\", pin=int(***)), headers={'Authorization': 'Bearer abc'}, \
+         access_token=Token(value='xyz'))"
+    );
+}
@@ -0,0 +1,118 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+/// How long to wait for `m-o` to print a line before giving up.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `m-o --follow` running on a temporary file, which is cleaned up when it's dropped.
+struct Follower {
+    path: PathBuf,
+    child: Child,
+    lines: Receiver<String>,
+}
+
+impl Follower {
+    /// Writes `contents` to a new temporary file named after `name`, and starts following it.
+    fn start(name: &str, contents: &str) -> Follower {
+        Follower::start_with(name, contents, &[])
+    }
+
+    /// Like `start`, but passes `args` to `m-o` too.
+    fn start_with(name: &str, contents: &str, args: &[&str]) -> Follower {
+        let path = std::env::temp_dir().join(format!("m-o-{}-{}.log", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+
+        let mut child = Command::new(env!("CARGO_BIN_EXE_m-o"))
+            .args(["--follow", "--color", "never"])
+            .args(args)
+            .arg(&path)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let stdout = child.stdout.take().unwrap();
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                if sender.send(line.unwrap()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Follower { path, child, lines }
+    }
+
+    fn append(&self, text: &str) {
+        let mut file = OpenOptions::new().append(true).open(&self.path).unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    fn expect(&self, expected: &[&str]) {
+        for expected in expected {
+            assert_eq!(self.lines.recv_timeout(TIMEOUT).unwrap(), *expected);
+        }
+    }
+}
+
+impl Drop for Follower {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = fs::remove_file(&self.path);
+        let _ = fs::remove_file(self.path.with_extension("log.1"));
+    }
+}
+
+#[test]
+fn test_follow_appended_lines() {
+    let follower = Follower::start("append", "Dog(name='Pip')\nnot data\n");
+    follower.expect(&["Dog(name='Pip')", "not data"]);
+
+    // Lines are only formatted once they're complete.
+    follower.append("[1, ");
+    thread::sleep(Duration::from_millis(500));
+    follower.append("2]\n");
+    follower.expect(&["[1, 2]"]);
+}
+
+#[test]
+fn test_follow_truncated_file() {
+    let follower = Follower::start("truncate", "Dog(name='Pip', age=7)\n");
+    follower.expect(&["Dog(name='Pip', age=7)"]);
+
+    File::create(&follower.path)
+        .unwrap()
+        .write_all(b"[3]\n")
+        .unwrap();
+    follower.expect(&["[3]"]);
+}
+
+#[test]
+fn test_follow_rotated_file() {
+    let follower = Follower::start("rotate", "1\n");
+    follower.expect(&["1"]);
+
+    // The new file is longer than the old one, so it can't be mistaken for a truncation.
+    fs::rename(&follower.path, follower.path.with_extension("log.1")).unwrap();
+    fs::write(&follower.path, "Dog(name='Rex', age=3)\n").unwrap();
+    follower.expect(&["Dog(name='Rex', age=3)"]);
+}
+
+#[test]
+fn test_follow_starts_at_last_lines() {
+    let contents: String = (1..=12).map(|i| format!("{}\n", i)).collect();
+    let follower = Follower::start("last-lines", &contents);
+    let expected: Vec<_> = (3..=12).map(|i| i.to_string()).collect();
+    follower.expect(&expected.iter().map(String::as_str).collect::<Vec<_>>());
+    follower.append("13\n");
+    follower.expect(&["13"]);
+
+    let follower = Follower::start_with("one-line", "[1]\n[2]\n", &["--lines", "1"]);
+    follower.expect(&["[2]"]);
+}
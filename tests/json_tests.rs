@@ -0,0 +1,104 @@
+use std::convert::TryFrom;
+
+use m_o::value::json::{JsonError, JsonOptions};
+use m_o::value::print::PrintOptions;
+use m_o::value::Value;
+
+fn to_json(input: &str, json: &JsonOptions) -> String {
+    try_to_json(input, json).unwrap()
+}
+
+fn try_to_json(input: &str, json: &JsonOptions) -> Result<String, JsonError> {
+    let options = PrintOptions::default();
+    let value = Value::try_from(input).unwrap();
+    Ok(value
+        .to_json_doc(&options, json)?
+        .pretty(options.columns)
+        .to_string())
+}
+
+#[test]
+fn test_json() {
+    let input = "Dog(2, name='Pip', tags=('a', 'b'), owner=None, good=True, weight=7.0, \
+                 toys={}, vet={1: 'Ann', None: False})";
+    assert_eq!(
+        to_json(input, &JsonOptions::default()),
+        r#"{
+    "__type__": "Dog",
+    "__args__": [
+        2
+    ],
+    "name": "Pip",
+    "tags": [
+        "a",
+        "b"
+    ],
+    "owner": null,
+    "good": true,
+    "weight": 7.0,
+    "toys": {},
+    "vet": {
+        "1": "Ann",
+        "null": false
+    }
+}"#
+    );
+
+    let json = JsonOptions {
+        compact: true,
+        type_field: "type".to_string(),
+    };
+    assert_eq!(
+        to_json(input, &json),
+        r#"{"type":"Dog","__args__":[2],"name":"Pip","tags":["a","b"],"owner":null,"good":true,"weight":7.0,"toys":{},"vet":{"1":"Ann","null":false}}"#
+    );
+}
+
+#[test]
+fn test_json_strings() {
+    let json = JsonOptions {
+        compact: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        to_json(
+            r#"['caf\xe9', "it's", 'say "hi"\n', '\\', '\x1b[0m\t\u2603', '\N{BULLET}']"#,
+            &json
+        ),
+        r#"["café","it's","say \"hi\"\n","\\","\u001b[0m\t☃","\\N{BULLET}"]"#
+    );
+    assert_eq!(
+        to_json("{(1, 'a'): Color.RED}", &json),
+        r#"{"(1, 'a')":"Color.RED"}"#
+    );
+}
+
+#[test]
+fn test_json_key_collisions() {
+    let json = JsonOptions::default();
+    assert_eq!(
+        try_to_json("Y(3, __args__=4)", &json)
+            .unwrap_err()
+            .to_string(),
+        "the kwarg `__args__` of `Y` has the same key as the positional args"
+    );
+    assert_eq!(
+        try_to_json("[Event(__type__='click')]", &json)
+            .unwrap_err()
+            .to_string(),
+        "the kwarg `__type__` of `Event` has the same key as the constructor name, which can be \
+         put under another key with `--type-field`"
+    );
+
+    // Without positional args, there's no `__args__` key to collide with.
+    assert_eq!(
+        to_json(
+            "Event(__args__=[], __type__='click')",
+            &JsonOptions {
+                compact: true,
+                type_field: "kind".to_string(),
+            }
+        ),
+        r#"{"kind":"Event","__args__":[],"__type__":"click"}"#
+    );
+}
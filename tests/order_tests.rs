@@ -0,0 +1,24 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+use m_o::value::Value;
+
+#[test]
+fn test_total_cmp() {
+    let cmp = |x: &str, y: &str| {
+        Value::try_from(x)
+            .unwrap()
+            .total_cmp(&Value::try_from(y).unwrap())
+    };
+
+    assert_eq!(cmp("1", "2.5"), Ordering::Less);
+    assert_eq!(cmp("True", "2"), Ordering::Less);
+    assert_eq!(cmp("-1.5", "False"), Ordering::Less);
+    assert_eq!(cmp("'b'", "\"a\""), Ordering::Greater);
+    assert_eq!(cmp("None", "0"), Ordering::Less);
+    assert_eq!(cmp("100", "'1'"), Ordering::Less);
+    assert_eq!(cmp("(1, 2)", "(1, 2, 0)"), Ordering::Less);
+    assert_eq!(cmp("Dog(age=7)", "Dog(age=8)"), Ordering::Less);
+    assert_eq!(cmp("Dog(1)", "Dog(age=0)"), Ordering::Less);
+    assert_eq!(cmp("[1, 'a']", "[1, 'a']"), Ordering::Equal);
+}
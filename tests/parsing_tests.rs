@@ -4,8 +4,8 @@ use nom::error::ErrorKind;
 
 use m_o::value::{
     parse::{
-        parse_bool, parse_constructor, parse_dict, parse_list, parse_set, parse_str, parse_symbol,
-        parse_tuple,
+        parse_bool, parse_complete_value, parse_constructor, parse_dict, parse_list, parse_set,
+        parse_str, parse_symbol, parse_tuple,
     },
     Arg, Value,
 };
@@ -66,11 +66,16 @@ fn test_symbol_with_dot() -> ParseResult<()> {
 fn test_str() -> ParseResult<()> {
     let txt = r#""double quoted""#;
     let (_rest, s) = parse_str(txt)?;
-    assert_eq!(s, Value::Str(txt));
+    assert_eq!(s, Value::Str(txt.into()));
 
     let txt = r#"'single quoted'"#;
     let (_rest, s) = parse_str(txt)?;
-    assert_eq!(s, Value::Str(txt));
+    assert_eq!(s, Value::Str(txt.into()));
+
+    for txt in &["''", r#""""#] {
+        let (_rest, s) = parse_str(txt)?;
+        assert_eq!(s, Value::Str((*txt).into()));
+    }
 
     Ok(())
 }
@@ -79,31 +84,31 @@ fn test_str() -> ParseResult<()> {
 fn test_str_escaping() -> ParseResult<()> {
     let txt = r#""escaped quote character (\") in a sentence.""#;
     let s = Value::try_from(txt)?;
-    assert_eq!(s, Value::Str(txt));
+    assert_eq!(s, Value::Str(txt.into()));
 
     let txt = r#"'escaped quote character (\') in a sentence.'"#;
     let s = Value::try_from(txt)?;
-    assert_eq!(s, Value::Str(txt));
+    assert_eq!(s, Value::Str(txt.into()));
 
     let txt = r#""what's up?""#;
     let s = Value::try_from(txt)?;
-    assert_eq!(s, Value::Str(txt));
+    assert_eq!(s, Value::Str(txt.into()));
 
     let txt = r#"'they told me "keep it down"'"#;
     let s = Value::try_from(txt)?;
-    assert_eq!(s, Value::Str(txt));
+    assert_eq!(s, Value::Str(txt.into()));
 
     let txt = r#"'I hadn\'t seen it coming.\n"who are you?" they said.'"#;
     let s = Value::try_from(txt)?;
-    assert_eq!(s, Value::Str(txt));
+    assert_eq!(s, Value::Str(txt.into()));
 
     let single_quote = r#"'\\ \' \a \b \f \n \N{name} \r \t \u1234 \U12341234 \v \012 \123 \234 \345 \456 \567 \670 \701 \x12'"#;
     let s = Value::try_from(single_quote)?;
-    assert_eq!(s, Value::Str(single_quote));
+    assert_eq!(s, Value::Str(single_quote.into()));
 
     let double_quote = r#""\\ \" \a \b \f \n \N{name} \r \t \u1234 \U12341234 \v \012 \123 \234 \345 \456 \567 \670 \701 \x12""#;
     let s = Value::try_from(double_quote)?;
-    assert_eq!(s, Value::Str(double_quote));
+    assert_eq!(s, Value::Str(double_quote.into()));
 
     Ok(())
 }
@@ -112,11 +117,11 @@ fn test_str_escaping() -> ParseResult<()> {
 fn test_quotes_inside_quotes() -> ParseResult<()> {
     let quote = r#"'"'"#;
     let s = Value::try_from(quote)?;
-    assert_eq!(s, Value::Str(quote));
+    assert_eq!(s, Value::Str(quote.into()));
 
     let quote = r#""'""#;
     let s = Value::try_from(quote)?;
-    assert_eq!(s, Value::Str(quote));
+    assert_eq!(s, Value::Str(quote.into()));
 
     Ok(())
 }
@@ -180,7 +185,7 @@ fn test_dict() -> ParseResult<()> {
         dict,
         Value::Dict(vec![
             (Value::Int(1), Value::Int(2)),
-            (Value::Str("'a'"), Value::Int(3)),
+            (Value::Str("'a'".into()), Value::Int(3)),
         ])
     );
 
@@ -241,7 +246,7 @@ fn test_mixed_args_and_kwargs_constructor() -> ParseResult<()> {
         Value::Constructor(
             "Dog",
             vec![
-                Arg::Arg(Value::Str("'Pip'")),
+                Arg::Arg(Value::Str("'Pip'".into())),
                 Arg::Kwarg("age", Value::Int(7)),
             ]
         )
@@ -264,7 +269,7 @@ fn test_big_value() -> ParseResult<()> {
                     Value::Int(2),
                     Value::List(vec![
                         Value::Bool(true),
-                        Value::List(vec![Value::Bool(false), Value::Str("'abc'")])
+                        Value::List(vec![Value::Bool(false), Value::Str("'abc'".into())])
                     ])
                 ])
             )]
@@ -292,3 +297,73 @@ fn test_float_vs_int_precedence() -> ParseResult<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_trailing_input() {
+    // `try_from` parses the value at the start of the input and ignores the rest.
+    assert_eq!(
+        Value::try_from("[1, 2] [3]"),
+        Ok(Value::List(vec![Value::Int(1), Value::Int(2)]))
+    );
+    assert_eq!(Value::try_from("Dog("), Ok(Value::Symbol("Dog")));
+
+    // `parse_complete_value` only accepts input which is a single value.
+    assert_eq!(
+        parse_complete_value("[1, 2]\n"),
+        Ok(("", Value::List(vec![Value::Int(1), Value::Int(2)])))
+    );
+    assert!(parse_complete_value("[1, 2] [3]").is_err());
+    assert!(parse_complete_value("Dog(").is_err());
+}
+
+#[test]
+fn test_multi_line_layout() -> ParseResult<()> {
+    let value = Value::try_from(
+        r#"Dog(
+    name="Pip",
+    friends=[
+        "Quincy",
+        "Digger",
+    ],
+    owners={
+        "Sam": (1, 2),
+    }
+)"#,
+    )?;
+    assert_eq!(
+        value,
+        Value::Constructor(
+            "Dog",
+            vec![
+                Arg::Kwarg("name", Value::Str(r#""Pip""#.into())),
+                Arg::Kwarg(
+                    "friends",
+                    Value::List(vec![
+                        Value::Str(r#""Quincy""#.into()),
+                        Value::Str(r#""Digger""#.into())
+                    ])
+                ),
+                Arg::Kwarg(
+                    "owners",
+                    Value::Dict(vec![(
+                        Value::Str(r#""Sam""#.into()),
+                        Value::Tuple(vec![Value::Int(1), Value::Int(2)])
+                    )])
+                ),
+            ]
+        )
+    );
+
+    let value = Value::try_from("(3,)")?;
+    assert_eq!(value, Value::Tuple(vec![Value::Int(3)]));
+
+    let value = Value::try_from("[1, 2,]")?;
+    assert_eq!(value, Value::List(vec![Value::Int(1), Value::Int(2)]));
+
+    // Only collections with items can have a trailing comma.
+    for input in &["[,]", "(,)", "{,}", "Dog(,)"] {
+        assert!(parse_complete_value(input).is_err(), "{}", input);
+    }
+
+    Ok(())
+}
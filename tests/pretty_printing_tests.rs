@@ -1,4 +1,8 @@
+use std::convert::TryFrom;
+
+use m_o::value::highlight::HighlightWriter;
 use m_o::value::print::PrintOptions;
+use m_o::value::theme::Theme;
 use m_o::value::{Arg, Value};
 
 fn value_to_string(value: &Value, columns: usize) -> String {
@@ -6,19 +10,23 @@ fn value_to_string(value: &Value, columns: usize) -> String {
         columns,
         ..Default::default()
     };
-    value.to_doc(&options).pretty(options.columns).to_string()
+    value_to_string_with(value, &options)
+}
+
+fn value_to_string_with(value: &Value, options: &PrintOptions) -> String {
+    value.to_doc(options).pretty(options.columns).to_string()
 }
 
 #[test]
 fn test_simple_value_to_string() {
     let list = Value::List(vec![
         Value::Int(123),
-        Value::Str("\"abc\""),
+        Value::Str("\"abc\"".into()),
         Value::Bool(true),
         Value::Constructor(
             "Dog",
             vec![
-                Arg::Kwarg("name", Value::Str("'Pip'")),
+                Arg::Kwarg("name", Value::Str("'Pip'".into())),
                 Arg::Kwarg("age", Value::Int(7)),
             ],
         ),
@@ -58,7 +66,7 @@ fn test_deep_nesting_to_string() {
             Value::Constructor(
                 "Dog",
                 vec![
-                    Arg::Kwarg("name", Value::Str("\"Pip\"")),
+                    Arg::Kwarg("name", Value::Str("\"Pip\"".into())),
                     Arg::Kwarg("age", Value::Int(7)),
                 ],
             ),
@@ -86,10 +94,11 @@ fn test_deep_nesting_to_string() {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn test_nested_dicts_to_string() {
     let dict = Value::Dict(vec![
         (
-            Value::Str("\"abc\""),
+            Value::Str("\"abc\"".into()),
             Value::Dict(vec![(
                 Value::Int(123),
                 Value::Dict(vec![
@@ -124,3 +133,353 @@ fn test_nested_dicts_to_string() {
 
     assert_eq!(value_to_string(&dict, 20), expected);
 }
+
+#[test]
+fn test_highlighted_value_to_string() {
+    let value = Value::Constructor("Dog", vec![Arg::Kwarg("age", Value::Int(7))]);
+    let options = PrintOptions::default();
+    let render = |theme| {
+        let mut out = Vec::new();
+        value
+            .to_doc(&options)
+            .render_raw(
+                options.columns,
+                &mut HighlightWriter::with_theme(&mut out, theme),
+            )
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    };
+
+    assert_eq!(render(None), "Dog(age=7)");
+    assert_eq!(
+        render(Some(Theme::default())),
+        "\u{1b}[1m\u{1b}[38;5;4mDog\u{1b}[m\u{1b}[38;5;8m(\u{1b}[m\u{1b}[38;5;6mage\u{1b}[m\
+         \u{1b}[38;5;8m=\u{1b}[m\u{1b}[38;5;5m7\u{1b}[m\u{1b}[38;5;8m)\u{1b}[m"
+    );
+}
+
+#[test]
+fn test_trailing_comma_to_string() {
+    let value = Value::Constructor(
+        "Dog",
+        vec![
+            Arg::Kwarg("name", Value::Str("\"Pip\"".into())),
+            Arg::Kwarg(
+                "friends",
+                Value::List(vec![Value::Str("\"Quincy\"".into())]),
+            ),
+            Arg::Kwarg("ids", Value::Tuple(vec![Value::Int(1)])),
+            Arg::Kwarg("toys", Value::List(vec![])),
+        ],
+    );
+    let to_string = |columns, trailing_comma| {
+        let options = PrintOptions {
+            columns,
+            trailing_comma,
+            ..Default::default()
+        };
+        value_to_string_with(&value, &options)
+    };
+
+    // Commas only appear when a group is broken.
+    assert_eq!(
+        to_string(80, true),
+        r#"Dog(name="Pip", friends=["Quincy"], ids=(1,), toys=[])"#
+    );
+    assert_eq!(
+        to_string(24, true),
+        r#"Dog(
+    name="Pip",
+    friends=["Quincy"],
+    ids=(1,),
+    toys=[],
+)"#
+    );
+    assert_eq!(
+        to_string(24, false),
+        r#"Dog(
+    name="Pip",
+    friends=["Quincy"],
+    ids=(1,),
+    toys=[]
+)"#
+    );
+}
+
+#[test]
+fn test_sorted_value_to_string() {
+    let value = Value::try_from("{'b': {3, 1.5, 'x', None, True}, 'a': {}}").unwrap();
+    let to_string = |sort_dicts, sort_sets| {
+        let options = PrintOptions {
+            sort_dicts,
+            sort_sets,
+            ..Default::default()
+        };
+        value_to_string_with(&value, &options)
+    };
+
+    assert_eq!(
+        to_string(false, false),
+        "{'b': {3, 1.5, 'x', None, True}, 'a': {}}"
+    );
+    assert_eq!(
+        to_string(true, false),
+        "{'a': {}, 'b': {3, 1.5, 'x', None, True}}"
+    );
+    assert_eq!(
+        to_string(true, true),
+        "{'a': {}, 'b': {None, True, 1.5, 3, 'x'}}"
+    );
+}
+
+#[test]
+fn test_max_depth_to_string() {
+    let value = Value::try_from(
+        "Owner(name='Ann', pets=[Dog(name='Pip', toys={'ball': 1}), Cat()], ids=(3,), tags=[])",
+    )
+    .unwrap();
+    let to_string = |max_depth| {
+        let options = PrintOptions {
+            columns: 100,
+            max_depth: Some(max_depth),
+            ..Default::default()
+        };
+        value_to_string_with(&value, &options)
+    };
+
+    assert_eq!(to_string(0), "Owner(...)");
+    assert_eq!(
+        to_string(1),
+        "Owner(name='Ann', pets=[...2 items], ids=(...1 item), tags=[])"
+    );
+    assert_eq!(
+        to_string(2),
+        "Owner(name='Ann', pets=[Dog(...), Cat()], ids=(3,), tags=[])"
+    );
+    assert_eq!(
+        to_string(3),
+        "Owner(name='Ann', pets=[Dog(name='Pip', toys={...1 key}), Cat()], ids=(3,), tags=[])"
+    );
+}
+
+#[test]
+fn test_max_items_to_string() {
+    let value = Value::List(vec![
+        Value::Tuple((0..10_000).map(Value::Int).collect()),
+        Value::Dict((0..5).map(|i| (Value::Int(i), Value::Int(i))).collect()),
+        Value::Set((0..3).map(Value::Int).collect()),
+    ]);
+    let to_string = |max_items, keep_last_items| {
+        let options = PrintOptions {
+            max_items: Some(max_items),
+            keep_last_items,
+            ..Default::default()
+        };
+        value_to_string_with(&value, &options)
+    };
+
+    assert_eq!(
+        to_string(3, false),
+        "[(0, 1, 2, ... 9,997 more ...), {0: 0, 1: 1, 2: 2, ... 2 more ...}, {0, 1, 2}]"
+    );
+    assert_eq!(
+        to_string(2, true),
+        "[
+    (0, 1, ... 9,996 more ..., 9998, 9999),
+    {0: 0, 1: 1, ... 1 more ..., 3: 3, 4: 4},
+    {0, 1, 2}
+]"
+    );
+    // Twice the maximum doesn't overflow, so nothing is elided.
+    let options = PrintOptions {
+        max_items: Some(usize::MAX),
+        keep_last_items: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        value_to_string_with(&Value::Set((0..3).map(Value::Int).collect()), &options),
+        "{0, 1, 2}"
+    );
+
+    // One-tuples are elided like other tuples, rather than printed with their comma.
+    let one_tuple = Value::Tuple(vec![Value::Int(7)]);
+    let options = PrintOptions {
+        max_items: Some(0),
+        ..Default::default()
+    };
+    assert_eq!(
+        value_to_string_with(&one_tuple, &options),
+        "(... 1 more ...)"
+    );
+}
+
+#[test]
+fn test_long_strings_to_string() {
+    let value = Value::try_from(
+        r"Blob(data='aGVsbG8gd29ybGQaGVsbG8gd29ybGQaGVsbG8gd29ybGQ\x1b\N{BULLET}\'', short='hi')",
+    )
+    .unwrap();
+    let to_string = |max_string_length, wrap_strings| {
+        let options = PrintOptions {
+            columns: 30,
+            max_string_length,
+            wrap_strings,
+            ..Default::default()
+        };
+        value_to_string_with(&value, &options)
+    };
+
+    assert_eq!(
+        to_string(Some(4), false),
+        "Blob(
+    data='aGVs'... (44 more chars),
+    short='hi'
+)"
+    );
+    // Escape sequences count as one character, and are never split.
+    assert_eq!(
+        to_string(Some(46), true),
+        r"Blob(
+    data=(
+        'aGVsbG8gd29ybGQaGVsb'
+        'G8gd29ybGQaGVsbG8gd2'
+        '9ybGQ\x1b'
+    )... (2 more chars),
+    short='hi'
+)"
+    );
+    assert_eq!(
+        to_string(None, true),
+        r"Blob(
+    data=(
+        'aGVsbG8gd29ybGQaGVsb'
+        'G8gd29ybGQaGVsbG8gd2'
+        '9ybGQ\x1b\N{BULLET}'
+        '\''
+    ),
+    short='hi'
+)"
+    );
+}
+
+#[test]
+fn test_aligned_value_to_string() {
+    let value =
+        Value::try_from("Dog(1, name='Pip', age=7, toys={'ball': 1, 'squeaky rope': 2})").unwrap();
+    let to_string = |columns| {
+        let options = PrintOptions {
+            columns,
+            align: true,
+            ..Default::default()
+        };
+        value_to_string_with(&value, &options)
+    };
+
+    // Flat layouts aren't padded.
+    assert_eq!(
+        to_string(80),
+        "Dog(1, name='Pip', age=7, toys={'ball': 1, 'squeaky rope': 2})"
+    );
+    assert_eq!(
+        to_string(40),
+        "Dog(
+    1,
+    name='Pip',
+    age =7,
+    toys={'ball': 1, 'squeaky rope': 2}
+)"
+    );
+    assert_eq!(
+        to_string(30),
+        "Dog(
+    1,
+    name='Pip',
+    age =7,
+    toys={
+        'ball'        : 1,
+        'squeaky rope': 2
+    }
+)"
+    );
+
+    // The padding is read back as whitespace.
+    for columns in [40, 30] {
+        let aligned = to_string(columns);
+        assert_eq!(Value::try_from(aligned.as_str()), Ok(value.clone()));
+    }
+}
+
+#[test]
+fn test_fill_layout_to_string() {
+    let value = Value::Dict(vec![
+        (
+            Value::Str("'ids'".into()),
+            Value::List((0..30).map(Value::Int).collect()),
+        ),
+        (
+            Value::Str("'names'".into()),
+            Value::Tuple(vec![Value::Str("'ann'".into()); 7]),
+        ),
+        (
+            Value::Str("'mixed'".into()),
+            Value::List(vec![
+                Value::Int(1),
+                Value::Str("'a'".into()),
+                Value::Float(2.5),
+            ]),
+        ),
+    ]);
+
+    assert_eq!(
+        value_to_string(&value, 30),
+        "{
+    'ids': [
+        0, 1, 2, 3, 4, 5, 6,
+        7, 8, 9, 10, 11, 12,
+        13, 14, 15, 16, 17,
+        18, 19, 20, 21, 22,
+        23, 24, 25, 26, 27,
+        28, 29
+    ],
+    'names': (
+        'ann', 'ann', 'ann',
+        'ann', 'ann', 'ann',
+        'ann'
+    ),
+    'mixed': [1, 'a', 2.5]
+}"
+    );
+}
+
+#[test]
+fn test_table_to_string() {
+    let value = Value::try_from(
+        "Result(
+            rows=[
+                Row(id=1, name='Ann', score=3.5, tags=['a']),
+                Row(id=22, name='Bartholomew', score=10.25, tags=[]),
+                Row(id=3, name=None, score=0.5, tags=['x', 'y']),
+            ],
+            mixed=[Row(id=1), Dog(id=2)],
+        )",
+    )
+    .unwrap();
+    let options = PrintOptions {
+        tables: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        value_to_string_with(&value, &options),
+        "Result(
+    rows=[  # Row
+        id  name           score  tags
+        --  -------------  -----  ----------
+         1  'Ann'            3.5  ['a']
+        22  'Bartholomew'  10.25  []
+         3  None             0.5  ['x', 'y']
+    ],
+    mixed=[Row(id=1), Dog(id=2)]
+)"
+    );
+}
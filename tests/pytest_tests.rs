@@ -0,0 +1,41 @@
+use m_o::pytest::parse_assertion;
+use m_o::value::{Arg, Value};
+
+#[test]
+fn test_assertion_error_line() {
+    let line = "E       AssertionError: assert Dog(name='Pip') == Dog(name='Pep')";
+    assert_eq!(
+        parse_assertion(line),
+        Some((
+            Value::Constructor("Dog", vec![Arg::Kwarg("name", Value::Str("'Pip'".into()))]),
+            Value::Constructor("Dog", vec![Arg::Kwarg("name", Value::Str("'Pep'".into()))]),
+        ))
+    );
+}
+
+#[test]
+fn test_bare_assert_line() {
+    assert_eq!(
+        parse_assertion("E   assert [1, 2] == [1, 3]"),
+        Some((
+            Value::List(vec![Value::Int(1), Value::Int(2)]),
+            Value::List(vec![Value::Int(1), Value::Int(3)]),
+        ))
+    );
+    assert_eq!(
+        parse_assertion("AssertionError: assert 1 == 2"),
+        Some((Value::Int(1), Value::Int(2)))
+    );
+}
+
+#[test]
+fn test_other_lines_are_ignored() {
+    // Quoted source code.
+    assert_eq!(parse_assertion(">       assert pip == pep"), None);
+    assert_eq!(parse_assertion("    assert pip == pep"), None);
+    // Other explanations.
+    assert_eq!(parse_assertion("E         Full diff:"), None);
+    assert_eq!(parse_assertion("E   assert 1 != 2"), None);
+    // Elided values.
+    assert_eq!(parse_assertion("E   assert Dog(...) == Dog(...)"), None);
+}
@@ -0,0 +1,30 @@
+use m_o::value::quotes::{requote, QuoteStyle};
+
+#[test]
+fn test_requote_preferred_quotes() {
+    assert_eq!(requote("'abc'", QuoteStyle::Double), "\"abc\"");
+    assert_eq!(requote("\"abc\"", QuoteStyle::Single), "'abc'");
+    assert_eq!(requote("'abc'", QuoteStyle::Preserve), "'abc'");
+    assert_eq!(requote(r#""it's""#, QuoteStyle::Preserve), r#""it's""#);
+    // Other escapes are kept as they are.
+    assert_eq!(requote(r"'a\nb\\'", QuoteStyle::Double), r#""a\nb\\""#);
+}
+
+#[test]
+fn test_requote_escapes_quotes() {
+    assert_eq!(requote(r"'it\'s'", QuoteStyle::Double), r#""it's""#);
+    assert_eq!(
+        requote(r#"'say "hi" and it\'s'"#, QuoteStyle::Single),
+        r#"'say "hi" and it\'s'"#
+    );
+    assert_eq!(
+        requote(r#"'say "hi" and it\'s'"#, QuoteStyle::Double),
+        r#"'say "hi" and it\'s'"#
+    );
+    // The other quotes are used when they need fewer escapes.
+    assert_eq!(
+        requote(r#""say \"hi\"""#, QuoteStyle::Double),
+        r#"'say "hi"'"#
+    );
+    assert_eq!(requote(r"'it\'s'", QuoteStyle::Single), r#""it's""#);
+}
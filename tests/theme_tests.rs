@@ -0,0 +1,69 @@
+use m_o::value::highlight::Highlight;
+use m_o::value::theme::{Color, Style, Theme, ThemeError};
+
+#[test]
+fn test_named_themes() {
+    assert_eq!(Theme::named("dark"), Some(Theme::dark()));
+    assert_eq!(Theme::named("light"), Some(Theme::light()));
+    assert_eq!(Theme::named("solarized"), None);
+}
+
+#[test]
+fn test_parse_theme() {
+    let theme = Theme::parse(
+        "# Comments and blank lines are ignored.
+
+        base = light
+        constructor = #ff8800 bold underline
+        kwarg = bright-cyan italic
+        bracket = 244
+        symbol = default",
+    )
+    .unwrap();
+
+    assert_eq!(
+        theme.style(Highlight::Constructor),
+        &Style {
+            fg: Some(Color::Rgb(0xff, 0x88, 0x00)),
+            bold: true,
+            italic: false,
+            underline: true,
+        }
+    );
+    assert_eq!(
+        theme.style(Highlight::Kwarg),
+        &Style {
+            fg: Some(Color::Ansi(14)),
+            italic: true,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        theme.style(Highlight::Punctuation).fg,
+        Some(Color::Ansi(244))
+    );
+    assert_eq!(theme.style(Highlight::Symbol), &Style::default());
+    // Unassigned kinds come from the base theme.
+    assert_eq!(theme.str, Theme::light().str);
+}
+
+#[test]
+fn test_parse_theme_errors() {
+    assert_eq!(
+        Theme::parse("str = green\nint = purple"),
+        Err(ThemeError {
+            line: 2,
+            message: "unknown color or style `purple`".to_string(),
+        })
+    );
+    assert_eq!(
+        Theme::parse("strings = green"),
+        Err(ThemeError {
+            line: 1,
+            message: "unknown kind of text `strings`".to_string(),
+        })
+    );
+    assert!(Theme::parse("str green").is_err());
+    assert!(Theme::parse("base = solarized").is_err());
+    assert!(Theme::parse("str = #12345").is_err());
+}
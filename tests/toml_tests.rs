@@ -0,0 +1,120 @@
+use std::convert::TryFrom;
+
+use m_o::value::print::PrintOptions;
+use m_o::value::toml::{TomlError, TomlOptions};
+use m_o::value::Value;
+
+fn to_toml(input: &str, toml: &TomlOptions) -> Result<String, TomlError> {
+    let options = PrintOptions::default();
+    let value = Value::try_from(input).unwrap();
+    Ok(value
+        .to_toml_doc(&options, toml)?
+        .pretty(options.columns)
+        .to_string())
+}
+
+#[test]
+fn test_toml() {
+    let input = "Settings(name='app', debug=False, ratio=0.5, ports=[80, 443], level=Level.INFO, \
+                 db=Database(host='localhost', options={'ssl mode': 'require'}), \
+                 servers=[Server(name='a', limits=Limits(cpu=2)), Server(name='b', limits={})], \
+                 points=[Point(1, 2)], extra={})";
+    assert_eq!(
+        to_toml(input, &TomlOptions::default()),
+        Ok(r#"name = "app"
+debug = false
+ratio = 0.5
+ports = [80, 443]
+level = "Level.INFO"
+extra = {}
+
+[db]
+host = "localhost"
+
+[db.options]
+"ssl mode" = "require"
+
+[[servers]]
+name = "a"
+
+[servers.limits]
+cpu = 2
+
+[[servers]]
+name = "b"
+limits = {}
+
+[[points]]
+__args__ = [1, 2]"#
+            .to_string())
+    );
+
+    let toml = TomlOptions {
+        type_field: Some("type".to_string()),
+    };
+    assert_eq!(
+        to_toml("Dog('Pip', toys=[1, Ball(size=2)])", &toml),
+        Ok(r#"type = "Dog"
+__args__ = ["Pip"]
+toys = [1, { type = "Ball", size = 2 }]"#
+            .to_string())
+    );
+}
+
+#[test]
+fn test_toml_errors() {
+    let toml = TomlOptions::default();
+    assert_eq!(
+        to_toml("Dog(name='Pip', vet=Vet(phone=None))", &toml)
+            .unwrap_err()
+            .path,
+        "vet.phone"
+    );
+    // Only kwargs can be left out with `--hide none`.
+    assert_eq!(
+        to_toml("Dog(name='Pip', vet=None)", &toml)
+            .unwrap_err()
+            .to_string(),
+        "`vet`: TOML has no `None`, but kwargs set to it can be left out with `--hide none`"
+    );
+    assert_eq!(
+        to_toml("Dog(toys=[1, None], vets={'Ann': None})", &toml)
+            .unwrap_err()
+            .to_string(),
+        "`toys`: TOML has no `None`"
+    );
+    assert_eq!(
+        to_toml("{'vet': None}", &toml).unwrap_err().to_string(),
+        "`vet`: TOML has no `None`"
+    );
+    assert_eq!(
+        to_toml("['Pip']", &toml).unwrap_err().to_string(),
+        "only constructors and dicts can be converted to TOML documents"
+    );
+}
+
+#[test]
+fn test_toml_duplicate_keys() {
+    let toml = TomlOptions::default();
+    assert_eq!(
+        to_toml("Y(3, __args__=4)", &toml).unwrap_err().to_string(),
+        "the kwarg `__args__` of `Y` has the same key as the positional args"
+    );
+    assert_eq!(
+        to_toml("Settings(db={1: 'a', '1': 'b'})", &toml)
+            .unwrap_err()
+            .to_string(),
+        "`db`: the key `1` appears more than once in the table"
+    );
+
+    let toml = TomlOptions {
+        type_field: Some("type".to_string()),
+    };
+    assert_eq!(
+        to_toml("Settings(db=Db(type='postgres'))", &toml)
+            .unwrap_err()
+            .to_string(),
+        "`db`: the kwarg `type` of `Db` has the same key as the constructor name, which can be \
+         put under another key with `--type-field`"
+    );
+}
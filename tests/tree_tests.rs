@@ -0,0 +1,79 @@
+use std::convert::TryFrom;
+
+use m_o::value::print::PrintOptions;
+use m_o::value::tree::TreeGuides;
+use m_o::value::Value;
+
+fn tree_to_string(input: &str, options: &PrintOptions, guides: TreeGuides) -> String {
+    let value = Value::try_from(input.trim()).unwrap();
+    value
+        .to_tree_doc(options, guides)
+        .pretty(options.columns)
+        .to_string()
+}
+
+#[test]
+fn test_simple_tree() {
+    assert_eq!(
+        tree_to_string(
+            include_str!("simple_tree.txt"),
+            &PrintOptions::default(),
+            TreeGuides::Unicode
+        ),
+        r#"Dog
+├── name: "Pip"
+├── age: 7
+└── friends: list
+    ├── "Quincy"
+    └── "Digger""#
+    );
+}
+
+#[test]
+fn test_ascii_tree() {
+    let options = PrintOptions {
+        max_depth: Some(6),
+        ..Default::default()
+    };
+    assert_eq!(
+        tree_to_string(include_str!("ast_test.txt"), &options, TreeGuides::Ascii),
+        "TopLevelStmts
+`-- stmts: list
+    `-- Call
+        |-- fn: Ident
+        |   `-- name: 'exit'
+        `-- arg: Call
+            |-- fn: Call
+            |   |-- fn: Ident
+            |   |   `-- name: '*'
+            |   `-- arg: Const
+            |       |-- value: 2
+            |       `-- _type: Int
+            `-- arg: Call
+                |-- fn: Call
+                |   |-- fn: Ident(...)
+                |   `-- arg: Call(...)
+                `-- arg: Call
+                    |-- fn: Call(...)
+                    `-- arg: Const(...)"
+    );
+}
+
+#[test]
+fn test_tree_leaves() {
+    assert_eq!(
+        tree_to_string(
+            "{'a': {(1, 2): [], 'b': Dog(1, x={1})}, 'c': ()}",
+            &PrintOptions::default(),
+            TreeGuides::Unicode
+        ),
+        "dict
+├── 'a': dict
+│   ├── (1, 2): []
+│   └── 'b': Dog
+│       ├── 1
+│       └── x: set
+│           └── 1
+└── 'c': ()"
+    );
+}
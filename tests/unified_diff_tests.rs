@@ -0,0 +1,72 @@
+use m_o::unified_diff::write_unified_diff;
+use m_o::value::theme::Theme;
+
+fn unified_diff(current: &str, formatted: &str, theme: Option<&Theme>) -> String {
+    let mut out = Vec::new();
+    write_unified_diff(&mut out, "dog.txt", current, formatted, theme).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_hunk_headers_and_context() {
+    let lines = |two: &str, eleven: &str| -> String {
+        (1..=12)
+            .map(|i| match i {
+                2 => format!("{}\n", two),
+                11 => format!("{}\n", eleven),
+                _ => format!("{}\n", i),
+            })
+            .collect()
+    };
+    let (current, formatted) = (lines("2", "11"), lines("two", "eleven"));
+    assert_eq!(
+        unified_diff(&current, &formatted, None),
+        "--- dog.txt\t(current)
++++ dog.txt\t(formatted)
+@@ -1,5 +1,5 @@
+ 1
+-2
++two
+ 3
+ 4
+ 5
+@@ -8,5 +8,5 @@
+ 8
+ 9
+ 10
+-11
++eleven
+ 12
+"
+    );
+}
+
+#[test]
+fn test_missing_trailing_newline() {
+    assert_eq!(
+        unified_diff("Dog(name='Pip')", "Dog(name='Pip')\n", None),
+        "--- dog.txt\t(current)
++++ dog.txt\t(formatted)
+@@ -1 +1 @@
+-Dog(name='Pip')
+\\ No newline at end of file
++Dog(name='Pip')
+"
+    );
+}
+
+#[test]
+fn test_equal_contents() {
+    assert_eq!(
+        unified_diff("[1, 2]\n", "[1, 2]\n", None),
+        "--- dog.txt\t(current)\n+++ dog.txt\t(formatted)\n"
+    );
+}
+
+#[test]
+fn test_styled_lines() {
+    let theme = Theme::dark();
+    let diff = unified_diff("1\n", "2\n", Some(&theme));
+    assert!(diff.contains(&format!("{}-1", theme.deleted.escape_code())));
+    assert!(diff.contains(&format!("{}+2", theme.inserted.escape_code())));
+}
@@ -0,0 +1,75 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// A temporary file named after `name`, which is removed when it's dropped.
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str, contents: &str) -> TempFile {
+        let path = std::env::temp_dir().join(format!("m-o-{}-{}.txt", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+        TempFile(path)
+    }
+
+    fn contents(&self) -> String {
+        fs::read_to_string(&self.0).unwrap()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn m_o(args: &[&str], file: &TempFile) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_m-o"))
+        .args(args)
+        .args(["--color", "never"])
+        .arg(&file.0)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_write_then_check() {
+    let file = TempFile::new("write", "Point(x=1.0,  y=-2.50, z=1.0e-07, scale=3)");
+    assert!(!m_o(&["--check"], &file).status.success());
+
+    assert!(m_o(&["--write"], &file).status.success());
+    // Whole-number floats keep their `.0`, so they're still floats when read back.
+    assert_eq!(file.contents(), "Point(x=1.0, y=-2.5, z=1.0e-7, scale=3)\n");
+
+    assert!(m_o(&["--check"], &file).status.success());
+    assert!(m_o(&["--write"], &file).status.success());
+    assert_eq!(file.contents(), "Point(x=1.0, y=-2.5, z=1.0e-7, scale=3)\n");
+}
+
+#[test]
+fn test_write_then_check_aligned() {
+    let file = TempFile::new(
+        "align",
+        "Dog(1, name='Pip', age=7, toys={'ball': 1, 'squeaky rope': 2})",
+    );
+    let args = ["--align", "--columns", "30"];
+    assert!(m_o(&[&args[..], &["--write"]].concat(), &file)
+        .status
+        .success());
+    assert_eq!(
+        file.contents(),
+        "Dog(
+    1,
+    name='Pip',
+    age =7,
+    toys={
+        'ball'        : 1,
+        'squeaky rope': 2
+    }
+)
+"
+    );
+    assert!(m_o(&[&args[..], &["--check"]].concat(), &file)
+        .status
+        .success());
+}
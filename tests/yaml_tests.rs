@@ -0,0 +1,114 @@
+use std::convert::TryFrom;
+
+use m_o::value::print::PrintOptions;
+use m_o::value::yaml::{YamlError, YamlOptions};
+use m_o::value::Value;
+
+fn to_yaml(input: &str, yaml: &YamlOptions) -> String {
+    try_to_yaml(input, yaml).unwrap()
+}
+
+fn try_to_yaml(input: &str, yaml: &YamlOptions) -> Result<String, YamlError> {
+    let options = PrintOptions {
+        indent: 2,
+        ..Default::default()
+    };
+    let value = Value::try_from(input).unwrap();
+    Ok(value
+        .to_yaml_doc(&options, yaml)?
+        .pretty(options.columns)
+        .to_string())
+}
+
+#[test]
+fn test_yaml() {
+    let input = "Dog('Pip', age=7, weight=7.0, owner=None, good=True, toys=[], \
+                 friends=[Dog(name='Rex'), ['a', 'b']], born=date(2020, 1, 1), vet={1: Empty()})";
+    assert_eq!(
+        to_yaml(input, &YamlOptions::default()),
+        r#"!Dog
+__args__:
+  - Pip
+age: 7
+weight: 7.0
+owner: null
+good: true
+toys: []
+friends:
+  - !Dog
+    name: Rex
+  - - a
+    - b
+born: !date
+  - 2020
+  - 1
+  - 1
+vet:
+  1: !Empty {}"#
+    );
+
+    let yaml = YamlOptions {
+        type_field: Some("type".to_string()),
+    };
+    assert_eq!(
+        to_yaml("[Dog(name='Rex'), date(2020, 1, 1)]", &yaml),
+        r#"- type: Dog
+  name: Rex
+- type: date
+  __args__:
+    - 2020
+    - 1
+    - 1"#
+    );
+}
+
+#[test]
+fn test_yaml_scalars() {
+    assert_eq!(
+        to_yaml(
+            r#"['plain text', '', 'yes', 'No', '42', '2020-01-01', '-x', 'a: b', 'a #b', 'caf\xe9',
+                "it's \"quoted\"\n", Color.RED, 1.5e100, -2.5e-07]"#,
+            &YamlOptions::default()
+        ),
+        r#"- plain text
+- ""
+- "yes"
+- "No"
+- "42"
+- "2020-01-01"
+- "-x"
+- "a: b"
+- "a #b"
+- café
+- "it's \"quoted\"\n"
+- Color.RED
+- 1.5e+100
+- -2.5e-7"#
+    );
+}
+
+#[test]
+fn test_yaml_key_collisions() {
+    assert_eq!(
+        try_to_yaml("{'pet': Dog(1, __args__=2)}", &YamlOptions::default())
+            .unwrap_err()
+            .to_string(),
+        "the kwarg `__args__` of `Dog` has the same key as the positional args"
+    );
+    let yaml = YamlOptions {
+        type_field: Some("kind".to_string()),
+    };
+    assert_eq!(
+        try_to_yaml("Dog(kind='pug')", &yaml)
+            .unwrap_err()
+            .to_string(),
+        "the kwarg `kind` of `Dog` has the same key as the constructor name, which can be put \
+         under another key with `--type-field`"
+    );
+
+    // Tagged constructors have no key for their names.
+    assert_eq!(
+        to_yaml("Dog(kind='pug')", &YamlOptions::default()),
+        "!Dog\nkind: pug"
+    );
+}